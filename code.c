@@ -3,7 +3,7 @@ void putchar(char c);
 typedef struct Node
 {
     int value;
-    void *next;
+    struct Node *next;
 } Node;
 
 typedef struct LinkedList
@@ -17,7 +17,7 @@ void free(Node *ptr);
 
 int push(LinkedList *list, int value)
 {
-    Node *node = malloc(12);
+    Node *node = malloc(sizeof(Node));
     node->value = value;
 
     if (list->first == 0)
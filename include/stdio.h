@@ -0,0 +1,12 @@
+#ifndef _STDIO_H
+#define _STDIO_H
+
+#include <stddef.h>
+
+#define EOF (-1)
+
+int putchar(int c);
+int getchar();
+int puts(char* s);
+
+#endif
@@ -0,0 +1,11 @@
+#ifndef _STRING_H
+#define _STRING_H
+
+#include <stddef.h>
+
+size_t strlen(char* string);
+int strcmp(char* lhs, char* rhs);
+void* memcpy(void* destination, void* source, size_t count);
+void* memset(void* destination, int value, size_t count);
+
+#endif
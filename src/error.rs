@@ -1,7 +1,7 @@
 use crate::{
     lexer::{tokens::TokenKind, SrcLocation},
-    parser::ast::{Expression, UnaryOps},
-    scope_builder::ast::{DataType, Variable},
+    parser::ast::{BinaryOps, Expression, UnaryOps},
+    scope_builder::ast::DataType,
 };
 
 #[derive(Debug, Clone)]
@@ -11,7 +11,10 @@ pub enum Error<'a> {
         found: TokenKind,
         location: SrcLocation<'a>,
     },
-    UnableToAssign {
+    NotAnLvalue {
+        location: SrcLocation<'a>,
+    },
+    AssignToConst {
         location: SrcLocation<'a>,
     },
     UnknownType {
@@ -20,6 +23,18 @@ pub enum Error<'a> {
     StructRedefinition {
         name: &'a str,
     },
+    EnumRedefinition {
+        name: &'a str,
+    },
+    EnumeratorRedefinition {
+        name: &'a str,
+    },
+    EnumeratorNotConstant {
+        name: &'a str,
+    },
+    EnumeratorOutOfRange {
+        name: &'a str,
+    },
     StructFieldRedefinition {
         struct_name: &'a str,
         field_name: &'a str,
@@ -35,6 +50,9 @@ pub enum Error<'a> {
     DerefOfNonPointer {
         expr: &'a Expression<'a>,
     },
+    AddressOfNonLvalue {
+        expression: &'a Expression<'a>,
+    },
     UnknownVariable {
         name: &'a str,
     },
@@ -49,16 +67,20 @@ pub enum Error<'a> {
         from: &'a Expression<'a>,
         to: &'a Expression<'a>,
     },
-    CannotAssignVariable {
-        assignment: &'a Expression<'a>,
-        variable: Variable<'a>,
-        name: &'a str
-    },
     ArrayIndexNotANumber {
         index: &'a Expression<'a>,
     },
     EmptyArray {},
     ArrayOfDifferentTypes {},
+    ArraySizeNotConstant {
+        length: &'a Expression<'a>,
+    },
+    ArraySizeNegative {
+        length: &'a Expression<'a>,
+    },
+    TooManyInitializers {
+        expression: &'a Expression<'a>,
+    },
     UnkownFunction {
         name: &'a str,
     },
@@ -75,9 +97,12 @@ pub enum Error<'a> {
         found: DataType<'a>,
         name: &'a str,
     },
-    VariableDeclarationOutsideOfFunction {
+    StaticInitializerNotConstant {
         name: &'a str,
     },
+    SizeOfIncompleteType {
+        data_type: DataType<'a>,
+    },
     VariableOfUnkownSize {
         name: &'a str,
         data_type: DataType<'a>,
@@ -97,10 +122,45 @@ pub enum Error<'a> {
         expression: &'a Expression<'a>,
         operation: UnaryOps<'a>
     },
+    BinaryOperandNotNumber {
+        expression: &'a Expression<'a>,
+        operation: BinaryOps,
+    },
     BreakNotInLoop {
     },
     ContinueNotInLoop {
     },
+    CaseNotInSwitch {
+        location: SrcLocation<'a>,
+    },
+    CaseLabelNotConstant {
+        location: SrcLocation<'a>,
+    },
+    DuplicateCaseLabel {
+        value: i64,
+        location: SrcLocation<'a>,
+    },
+    DuplicateDefault {
+        location: SrcLocation<'a>,
+    },
+    SwitchOnNonNumber {
+        expression: &'a Expression<'a>,
+    },
+    StaticAssertNotConstant {
+        location: SrcLocation<'a>,
+    },
+    StaticAssertionFailed {
+        message: Option<&'a str>,
+        location: SrcLocation<'a>,
+    },
+    UndefinedLabel {
+        name: &'a str,
+        location: SrcLocation<'a>,
+    },
+    DuplicateLabel {
+        name: &'a str,
+        location: SrcLocation<'a>,
+    },
     SingleStatementMayNotBeDeclaration {
         name: &'a str,
     },
@@ -109,5 +169,62 @@ pub enum Error<'a> {
     },
     FunctionDefinitionNotSameAsDeclaration {
         name: &'a str,
-    }
+    },
+    InvalidDirective {
+        location: SrcLocation<'a>,
+    },
+    CannotOpenFile {
+        name: &'a str,
+        location: SrcLocation<'a>,
+    },
+    MacroArgumentCountMismatch {
+        name: &'a str,
+        expected: usize,
+        found: usize,
+        location: SrcLocation<'a>,
+    },
+    UnterminatedMacroCall {
+        name: &'a str,
+        location: SrcLocation<'a>,
+    },
+    UnterminatedConditional {
+        location: SrcLocation<'a>,
+    },
+    UnmatchedDirective {
+        location: SrcLocation<'a>,
+    },
+    InvalidConstantExpression {
+        location: SrcLocation<'a>,
+    },
+    RecursiveInclude {
+        name: &'a str,
+        location: SrcLocation<'a>,
+    },
+    InvalidTokenPaste {
+        location: SrcLocation<'a>,
+    },
+    InvalidIntegerLiteral {
+        location: SrcLocation<'a>,
+    },
+    IntegerLiteralOutOfRange {
+        location: SrcLocation<'a>,
+    },
+    InvalidCharLiteral {
+        location: SrcLocation<'a>,
+    },
+    InvalidEscapeSequence {
+        location: SrcLocation<'a>,
+    },
+    UnknownCharacter {
+        location: SrcLocation<'a>,
+    },
+    UnterminatedString {
+        location: SrcLocation<'a>,
+    },
+    UnterminatedCharLiteral {
+        location: SrcLocation<'a>,
+    },
+    UnterminatedComment {
+        location: SrcLocation<'a>,
+    },
 }
@@ -6,21 +6,24 @@ use std::{
 };
 
 use crate::{
-    parser::ast::{BinaryOps, UnaryOps},
+    parser::ast::{AssignOperation, BinaryOps, UnaryOps},
     scope_builder::ast::{
         resolved_array_expression::ResolvedArrayExpression,
         resolved_assignment::ResolvedAssignment,
         resolved_compound::ResolvedCompound,
+        resolved_do_while::ResolvedDoWhileStatement,
         resolved_expression::ResolvedExpression,
         resolved_for::ResolvedForStatement,
         resolved_function::ResolvedFunction,
         resolved_function_call::ResolvedFunctionCall,
+        resolved_global::{ResolvedGlobal, Section, StaticValue},
         resolved_if::{ResolvedElsePart, ResolvedIfStatement},
         resolved_program::ResolvedProgram,
         resolved_statement::ResolvedStatement,
         resolved_struct_expression::ResolvedStructExpression,
+        resolved_switch::ResolvedSwitchStatement,
         resolved_while::ResolvedWhileStatement,
-        DataType,
+        DataType, StorageClass,
     },
     visitor::{Visitable, Visitor},
 };
@@ -77,8 +80,8 @@ impl Generator {
         self.emit_ins("cmp ", from, to)
     }
 
-    pub fn cdq(&mut self) -> Result<usize, Error> {
-        self.emit("\tcdq\n")
+    pub fn cqo(&mut self) -> Result<usize, Error> {
+        self.emit("\tcqo\n")
     }
 
     pub fn jmp(&mut self, label: &str) -> Result<usize, Error> {
@@ -102,6 +105,27 @@ impl Generator {
         result
     }
 
+    /// shifts `to` by the amount in `from`, which has to go through %cl.
+    /// %rcx may hold a function argument, so it is saved.
+    pub fn gen_shift(
+        &mut self,
+        ins: &str,
+        from_size: usize,
+        from: Reg,
+        to: Reg,
+    ) -> Result<usize, Error> {
+        let size = Reg::set_size(8);
+        self.emit_sins("push", Reg::RCX)?;
+        Reg::set_size(from_size);
+        self.mov(from, Reg::RCX)?;
+        Reg::set_size(size);
+        self.emit(&format!("\t{}\t%cl, {}\n", ins, to))?;
+        Reg::set_size(8);
+        let result = self.emit_sins("pop", Reg::RCX);
+        Reg::set_size(size);
+        result
+    }
+
     pub fn lea(&mut self, from: Reg, to: Reg) -> Result<usize, Error> {
         Reg::set_size(8);
         self.emit_ins("lea ", from, to)
@@ -130,14 +154,24 @@ impl Generator {
         self.emit("\tret\n")
     }
 
-    pub fn emit_string(&mut self, label: i32, string: &str) -> Result<usize, Error> {
+    pub fn emit_string(&mut self, label: i32, string: &[u8]) -> Result<usize, Error> {
+        // everything but printable ascii is written as an octal escape
+        let mut escaped = String::from("\"");
+        for byte in string {
+            match byte {
+                b'"' | b'\\' => escaped.push_str(&format!("\\{}", *byte as char)),
+                b' '..=b'~' => escaped.push(*byte as char),
+                _ => escaped.push_str(&format!("\\{:03o}", byte)),
+            }
+        }
+        escaped.push('"');
         self.emit(&format!(
             "    .section   .rodata
 .LC{}:
     .string	{}
     .text
 ",
-            label, string
+            label, escaped
         ))
     }
 
@@ -158,6 +192,119 @@ impl Generator {
         )
     }
 
+    pub fn generate_case_name(label_index: i32) -> String {
+        format!("_case{}", label_index)
+    }
+
+    pub fn generate_goto_name(label_index: i32) -> String {
+        format!("_goto{}", label_index)
+    }
+
+    /// sign extends the lower `from_size` bytes of `reg` to all 64 bits
+    pub fn sign_extend(&mut self, from_size: usize, reg: Reg) -> Result<usize, Error> {
+        let ins = match from_size {
+            1 => "movsbq",
+            2 => "movswq",
+            4 => "movslq",
+            _ => return Ok(0),
+        };
+        Reg::set_size(from_size);
+        let from = format!("{}", reg);
+        Reg::set_size(8);
+        self.emit(&format!("\t{}\t{}, {}\n", ins, from, reg))
+    }
+
+    /// zero extends the lower `from_size` bytes of `reg` to all 64 bits
+    pub fn zero_extend(&mut self, from_size: usize, reg: Reg) -> Result<usize, Error> {
+        let ins = match from_size {
+            1 => "movzbq",
+            2 => "movzwq",
+            // writing the lower half clears the upper one
+            4 => "mov ",
+            _ => return Ok(0),
+        };
+        Reg::set_size(from_size);
+        let from = format!("{}", reg);
+        if from_size != 4 {
+            Reg::set_size(8);
+        }
+        let result = self.emit(&format!("\t{}\t{}, {}\n", ins, from, reg));
+        Reg::set_size(8);
+        result
+    }
+
+    /// extends `reg` holding a `data_type` to 64 bits
+    pub fn extend(&mut self, data_type: DataType, reg: Reg) -> Result<usize, Error> {
+        match data_type.is_signed() {
+            true => self.sign_extend(data_type.size(), reg),
+            false => self.zero_extend(data_type.size(), reg),
+        }
+    }
+
+    /// turns the value of `size` bytes in `reg` into 0 or 1
+    pub fn to_bool(&mut self, size: usize, reg: Reg) -> Result<usize, Error> {
+        Reg::set_size(size);
+        self.cmp(Reg::IMMEDIATE(0), reg)?;
+        Reg::set_size(1);
+        self.emit_sins("setne", reg)
+    }
+
+    /// jumps through a table in .rodata that holds the offset of every case
+    /// label from `min` to `max` relative to the table
+    fn jump_table(
+        &mut self,
+        label_index: i32,
+        cases: &[(i64, i32)],
+        (min, max): (i64, i64),
+        default: &str,
+    ) -> Result<usize, Error> {
+        let table = format!("_table{}", label_index);
+        let value = Reg::push();
+        let address = Reg::current();
+        Reg::set_size(8);
+        self.sub(Reg::IMMEDIATE(min), value)?;
+        self.cmp(Reg::IMMEDIATE(max - min), value)?;
+        self.emit(&format!("\tja\t\t{}\n", default))?;
+        self.emit(&format!("\tlea \t{}(%rip), {}\n", table, address))?;
+        self.emit(&format!(
+            "\tmovslq\t({}, {}, 4), {}\n",
+            address, value, value
+        ))?;
+        self.add(address, value)?;
+        self.emit(&format!("\tjmp \t*{}\n", value))?;
+        Reg::pop();
+
+        self.emit("    .section   .rodata\n    .align 4\n")?;
+        self.emit_label(&table)?;
+        for case in min..=max {
+            let target = match cases.iter().find(|(x, _)| *x == case) {
+                Some((_, label)) => Self::generate_case_name(*label),
+                None => default.to_string(),
+            };
+            self.emit(&format!("    .long   {} - {}\n", target, table))?;
+        }
+        self.emit("    .text\n")
+    }
+
+    /// compares the value with every case label one after another
+    fn compare_chain(&mut self, cases: &[(i64, i32)], default: &str) -> Result<usize, Error> {
+        let value = Reg::push();
+        let immediate = Reg::current();
+        Reg::set_size(8);
+        for (case, label) in cases {
+            if i32::try_from(*case).is_ok() {
+                self.cmp(Reg::IMMEDIATE(*case), value)?;
+            } else {
+                // cmp only takes 32 bit immediates
+                self.mov(Reg::IMMEDIATE(*case), immediate)?;
+                self.cmp(immediate, value)?;
+            }
+            self.je(&Self::generate_case_name(*label))?;
+        }
+        Reg::pop();
+        self.jmp(default)
+    }
+
     fn mov_bytes(&mut self, from: Reg, to: Reg, bytes: usize) -> Result<usize, Error> {
         let total_size = bytes;
         let mut bytes_to_copy = total_size;
@@ -181,6 +328,274 @@ impl Generator {
         Ok(0)
     }
 
+    /// clears `bytes` bytes on the stack, starting at `offset`
+    fn zero_stack(&mut self, offset: usize, bytes: usize) -> Result<usize, Error> {
+        let zero = Reg::current();
+        Reg::set_size(8);
+        self.mov(Reg::IMMEDIATE(0), zero)?;
+        let mut cleared = 0;
+        while cleared < bytes {
+            let size = match bytes - cleared {
+                1 => 1,
+                2..=3 => 2,
+                4..=7 => 4,
+                _ => 8,
+            };
+            Reg::set_size(size);
+            self.mov(
+                zero,
+                Reg::STACK {
+                    offset: offset - cleared,
+                },
+            )?;
+            cleared += size;
+        }
+        Ok(0)
+    }
+
+    /// applies `operation` to `to` and `from` that both hold a `data_type`,
+    /// the result is left in `to`
+    fn binary_operation(
+        &mut self,
+        operation: BinaryOps,
+        data_type: DataType,
+        from_size: usize,
+        from: Reg,
+        to: Reg,
+    ) -> Result<usize, Error> {
+        let signed = data_type.is_signed();
+        match operation {
+            BinaryOps::ADD => self.add(from, to),
+            BinaryOps::SUB => self.sub(from, to),
+            // there is no imul for bytes, the lower byte is the same anyway
+            BinaryOps::MUL if Reg::get_size() == 1 => {
+                Reg::set_size(4);
+                self.mul(from, to)?;
+                Reg::set_size(1);
+                Ok(0)
+            }
+            BinaryOps::MUL => self.mul(from, to),
+            BinaryOps::DIV | BinaryOps::MOD => self.divide(operation, data_type, from, to),
+            BinaryOps::EQ => self.gen_cmp("sete", from, to),
+            BinaryOps::NE => self.gen_cmp("setne", from, to),
+            BinaryOps::LT if signed => self.gen_cmp("setl", from, to),
+            BinaryOps::GT if signed => self.gen_cmp("setg", from, to),
+            BinaryOps::LE if signed => self.gen_cmp("setle", from, to),
+            BinaryOps::GE if signed => self.gen_cmp("setge", from, to),
+            BinaryOps::LT => self.gen_cmp("setb", from, to),
+            BinaryOps::GT => self.gen_cmp("seta", from, to),
+            BinaryOps::LE => self.gen_cmp("setbe", from, to),
+            BinaryOps::GE => self.gen_cmp("setae", from, to),
+            BinaryOps::BITAND => self.emit_ins("and ", from, to),
+            BinaryOps::BITOR => self.emit_ins("or  ", from, to),
+            BinaryOps::XOR => self.emit_ins("xor ", from, to),
+            BinaryOps::SHL => self.gen_shift("sal", from_size, from, to),
+            BinaryOps::SHR if signed => self.gen_shift("sar", from_size, from, to),
+            BinaryOps::SHR => self.gen_shift("shr", from_size, from, to),
+            _ => panic!("Something went wrong"),
+        }
+    }
+
+    /// `to / from` or `to % from`. Both are extended to 64 bits first, so
+    /// every size is divided the same way.
+    fn divide(
+        &mut self,
+        operation: BinaryOps,
+        data_type: DataType,
+        from: Reg,
+        to: Reg,
+    ) -> Result<usize, Error> {
+        let size = Reg::get_size();
+        self.extend(data_type, from)?;
+        self.extend(data_type, to)?;
+        Reg::set_size(8);
+        self.mov(from, Reg::RBX)?;
+        self.mov(to, Reg::RAX)?;
+        if data_type.is_signed() {
+            self.cqo()?;
+            self.emit_sins("idiv", Reg::RBX)?;
+        } else {
+            self.mov(Reg::IMMEDIATE(0), Reg::RDX)?;
+            self.emit_sins("div ", Reg::RBX)?;
+        }
+        let result = match operation {
+            BinaryOps::DIV => Reg::RAX,
+            _ => Reg::RDX,
+        };
+        self.mov(result, to)?;
+        Reg::set_size(size);
+        Ok(0)
+    }
+
+    /// computes the address of an lvalue into the current register. Structs
+    /// are always used by their address, so their value is the address.
+    fn generate_address<'a>(
+        &mut self,
+        expression: &ResolvedExpression<'a>,
+    ) -> Result<usize, Error> {
+        match expression {
+            ResolvedExpression::NamedVariable { variable } => match variable.storage {
+                StorageClass::Auto => self.lea(
+                    Reg::STACK {
+                        offset: variable.stack_offset,
+                    },
+                    Reg::current(),
+                ),
+                StorageClass::Static(label) => {
+                    Reg::set_size(8);
+                    self.emit(&format!("\tlea \t{}(%rip), {}\n", label, Reg::current()))
+                }
+            },
+            ResolvedExpression::Unary {
+                expression,
+                operation: UnaryOps::DEREF,
+                ..
+            } => expression.accept(self),
+            ResolvedExpression::Indexing {
+                index,
+                operand,
+                data_type,
+            } => {
+                operand.accept(self)?;
+                let address = Reg::push();
+                index.accept(self)?;
+                let index = Reg::pop();
+
+                Reg::set_size(8);
+                self.mul(Reg::IMMEDIATE(data_type.size() as i64), index)?;
+                self.add(index, address)
+            }
+            ResolvedExpression::FieldAccess {
+                field_offset,
+                operand,
+                ..
+            }
+            | ResolvedExpression::ArrowAccess {
+                field_offset,
+                operand,
+                ..
+            } => {
+                // the operand is either a struct or a pointer to one
+                operand.accept(self)?;
+                Reg::set_size(8);
+                self.add(Reg::IMMEDIATE(*field_offset as i64), Reg::current())
+            }
+            _ => panic!("not an lvalue: {:?}", expression),
+        }
+    }
+
+    /// the value of an lvalue, for structs and arrays that is its address
+    fn load<'a>(&mut self, expression: &ResolvedExpression<'a>) -> Result<usize, Error> {
+        self.generate_address(expression)?;
+        match expression.data_type() {
+            DataType::Struct(_) | DataType::Array(..) => Ok(0),
+            data_type => {
+                let address = Reg::current().as_address();
+                Reg::set_size(data_type.size());
+                self.mov(address, Reg::current())
+            }
+        }
+    }
+
+    /// stores `value` at `target` or combines it with the value there. The
+    /// address in `target` is computed before, so it is evaluated only once.
+    /// The result of the assignment is left in the current register.
+    fn store<'a>(
+        &mut self,
+        target: Reg,
+        data_type: DataType<'a>,
+        value: &ResolvedExpression<'a>,
+        operation: AssignOperation,
+    ) -> Result<usize, Error> {
+        let size = data_type.size();
+        match operation {
+            AssignOperation::Assign => {
+                value.accept(self)?;
+                if data_type == DataType::BOOL && value.data_type() != DataType::BOOL {
+                    self.to_bool(value.data_type().size(), Reg::current())?;
+                }
+                Reg::set_size(size);
+                self.mov(Reg::current(), target)
+            }
+            AssignOperation::Compound(operation) => {
+                let op_type = Self::operation_type(data_type, value, operation);
+                let result = Reg::current();
+                Reg::set_size(size);
+                self.mov(target, result)?;
+                if op_type.size() > size {
+                    self.extend(data_type, result)?;
+                }
+                Reg::push();
+                value.accept(self)?;
+                let value_reg = Reg::pop();
+                Reg::set_size(op_type.size());
+                let value_size = value.data_type().size();
+                self.binary_operation(operation, op_type, value_size, value_reg, result)?;
+                if data_type == DataType::BOOL {
+                    self.to_bool(op_type.size(), result)?;
+                }
+                Reg::set_size(size);
+                self.mov(result, target)
+            }
+            AssignOperation::Postfix(operation) => {
+                // the old value stays in the current register
+                let op_type = Self::operation_type(data_type, value, operation);
+                let old = Reg::current();
+                Reg::set_size(size);
+                self.mov(target, old)?;
+                Reg::push();
+                let new = Reg::current();
+                self.mov(old, new)?;
+                if op_type.size() > size {
+                    self.extend(data_type, new)?;
+                }
+                Reg::push();
+                value.accept(self)?;
+                let value_reg = Reg::pop();
+                Reg::set_size(op_type.size());
+                let value_size = value.data_type().size();
+                self.binary_operation(operation, op_type, value_size, value_reg, new)?;
+                if data_type == DataType::BOOL {
+                    self.to_bool(op_type.size(), new)?;
+                }
+                Reg::set_size(size);
+                self.mov(new, target)?;
+                Reg::pop();
+                Reg::set_size(size);
+                Ok(0)
+            }
+        }
+    }
+
+    /// the type a compound assignment to a `data_type` is calculated in,
+    /// the value is already converted to it
+    fn operation_type<'a>(
+        data_type: DataType<'a>,
+        value: &ResolvedExpression<'a>,
+        operation: BinaryOps,
+    ) -> DataType<'a> {
+        match operation {
+            BinaryOps::SHL | BinaryOps::SHR => data_type.promote(),
+            _ if data_type.is_number() => value.data_type(),
+            _ => data_type,
+        }
+    }
+
+    /// `store` to the address in the current register, the result replaces
+    /// the address
+    fn store_at_current<'a>(
+        &mut self,
+        data_type: DataType<'a>,
+        value: &ResolvedExpression<'a>,
+        operation: AssignOperation,
+    ) -> Result<usize, Error> {
+        let address = Reg::push();
+        self.store(address.as_address(), data_type, value, operation)?;
+        let result = Reg::pop();
+        Reg::set_size(data_type.size());
+        self.mov(result, address)
+    }
+
     fn generate_and_or<'a>(&mut self, expression: &ResolvedExpression<'a>) -> Result<usize, Error> {
         if let ResolvedExpression::BinaryExpression {
             lhs,
@@ -189,35 +604,28 @@ impl Generator {
             ..
         } = expression
         {
-            let first_reg = Reg::current();
+            // the right side is skipped once the result is known, the
+            // flags of the last comparison give the result
+            let (_, end_label) = self.generate_clause_names();
+            let reg = Reg::current();
             lhs.accept(self)?;
-
-            let (second_expression_label, end_label) = self.generate_clause_names();
+            self.cmp(Reg::IMMEDIATE(0), reg)?;
             match *operation {
-                BinaryOps::AND => {
-                    self.cmp(Reg::IMMEDIATE(0), first_reg)?;
-                    self.jne(&second_expression_label)?;
-                    self.jmp(&end_label)
-                }
-                BinaryOps::OR => {
-                    self.cmp(Reg::IMMEDIATE(0), first_reg)?;
-                    self.je(&second_expression_label)?;
-                    self.jmp(&end_label)
-                }
+                BinaryOps::AND => self.je(&end_label),
+                BinaryOps::OR => self.jne(&end_label),
                 _ => panic!("Wrong operation for boolean comparision!"),
             }?;
-            self.emit_label(&second_expression_label)?;
             rhs.accept(self)?;
-            let second_reg = Reg::current();
-
-            self.cmp(Reg::IMMEDIATE(0), second_reg)?;
-            self.mov(Reg::IMMEDIATE(1), second_reg)?;
-
-            let prev = Reg::set_size(1);
-            self.emit_sins("setne", second_reg)?;
-            Reg::set_size(prev);
-
-            return self.emit_label(&end_label);
+            self.cmp(Reg::IMMEDIATE(0), reg)?;
+            self.emit_label(&end_label)?;
+
+            // mov leaves the flags alone
+            Reg::set_size(4);
+            self.mov(Reg::IMMEDIATE(0), reg)?;
+            Reg::set_size(1);
+            self.emit_sins("setne", reg)?;
+            Reg::set_size(4);
+            return Ok(0);
         }
         panic!("this should not happen!");
     }
@@ -233,6 +641,9 @@ impl<'a> Visitor<&ResolvedProgram<'a>, Result<usize, Error>> for Generator {
 "
             .to_string(),
         )?;
+        for x in &visitor.globals {
+            x.accept(self)?;
+        }
         for x in &visitor.functions {
             x.accept(self)?;
         }
@@ -240,6 +651,70 @@ impl<'a> Visitor<&ResolvedProgram<'a>, Result<usize, Error>> for Generator {
     }
 }
 
+impl<'a> Visitor<&ResolvedGlobal<'a>, Result<usize, Error>> for Generator {
+    fn visit(&mut self, visitor: &ResolvedGlobal<'a>) -> Result<usize, Error> {
+        // the strings it points to are emitted first
+        for (_, value) in &visitor.values {
+            if let StaticValue::String {
+                string,
+                label_index,
+            } = value
+            {
+                self.emit_string(*label_index, string)?;
+            }
+        }
+        let section = match visitor.section {
+            Section::Data => ".data",
+            Section::Bss => ".bss",
+            Section::Rodata => ".section   .rodata",
+            Section::DataRelRo => ".section   .data.rel.ro,\"aw\"",
+        };
+        self.emit(&format!("    {}\n", section))?;
+        if visitor.exported {
+            self.emit(&format!("    .globl  {}\n", visitor.label))?;
+        }
+        self.emit(&format!("    .align  {}\n", visitor.data_type.alignment()))?;
+        self.emit_label(visitor.label)?;
+
+        let mut values: Vec<_> = visitor.values.iter().collect();
+        values.sort_by_key(|(offset, _)| *offset);
+        let mut offset = 0;
+        for (value_offset, value) in values {
+            // only the first field of a union can be initialised
+            if visitor.section == Section::Bss || *value_offset < offset {
+                continue;
+            }
+            if *value_offset > offset {
+                self.emit(&format!("    .zero   {}\n", value_offset - offset))?;
+            }
+            let (directive, size) = match value {
+                StaticValue::Integer { value, size } => {
+                    let directive = match size {
+                        1 => ".byte",
+                        2 => ".short",
+                        4 => ".long",
+                        _ => ".quad",
+                    };
+                    (format!("{}   {}", directive, value), *size)
+                }
+                StaticValue::String { label_index, .. } => {
+                    (format!(".quad   .LC{}", label_index), 8)
+                }
+                StaticValue::Address { label, offset } => {
+                    (format!(".quad   {}{:+}", label, offset), 8)
+                }
+            };
+            self.emit(&format!("    {}\n", directive))?;
+            offset = value_offset + size;
+        }
+        let size = visitor.data_type.size();
+        if size > offset {
+            self.emit(&format!("    .zero   {}\n", size - offset))?;
+        }
+        self.emit("    .text\n")
+    }
+}
+
 impl<'a> Visitor<&ResolvedCompound<'a>, Result<usize, Error>> for Generator {
     fn visit(&mut self, visitor: &ResolvedCompound<'a>) -> Result<usize, Error> {
         for s in &visitor.statements {
@@ -264,6 +739,17 @@ impl<'a> Visitor<&ResolvedStatement<'a>, Result<usize, Error>> for Generator {
             ResolvedStatement::SingleExpression(expression) => expression.accept(self),
             ResolvedStatement::ForStatement(statement) => statement.accept(self),
             ResolvedStatement::WhileStatement(while_statement) => while_statement.accept(self),
+            ResolvedStatement::SwitchStatement(switch_statement) => switch_statement.accept(self),
+            ResolvedStatement::DoWhileStatement(statement) => statement.accept(self),
+            ResolvedStatement::Case(label_index) => {
+                self.emit_label(&Self::generate_case_name(*label_index))
+            }
+            ResolvedStatement::Label(label_index) => {
+                self.emit_label(&Self::generate_goto_name(*label_index))
+            }
+            ResolvedStatement::Goto(label_index) => {
+                self.jmp(&Self::generate_goto_name(*label_index))
+            }
             ResolvedStatement::Compound(list) => list.accept(self),
             ResolvedStatement::Empty => Ok(0),
             ResolvedStatement::Continue(label_index) => {
@@ -332,12 +818,29 @@ impl<'a> Visitor<&ResolvedExpression<'a>, Result<usize, Error>> for Generator {
                 Reg::set_size(4);
                 self.mov(Reg::IMMEDIATE(*value as i64), Reg::current())
             }
+            ResolvedExpression::UIntLiteral(value) => {
+                Reg::set_size(4);
+                self.mov(Reg::IMMEDIATE(*value as i64), Reg::current())
+            }
+            ResolvedExpression::LongLiteral(value) => {
+                Reg::set_size(8);
+                self.mov(Reg::IMMEDIATE(*value), Reg::current())
+            }
+            ResolvedExpression::ULongLiteral(value) => {
+                Reg::set_size(8);
+                self.mov(Reg::IMMEDIATE(*value as i64), Reg::current())
+            }
             ResolvedExpression::CharLiteral(value) => {
                 Reg::set_size(1);
                 self.mov(Reg::IMMEDIATE(*value as i64), Reg::current())
             }
+            ResolvedExpression::NamedVariable { variable }
+                if matches!(variable.storage, StorageClass::Static(_)) =>
+            {
+                self.load(visitor)
+            }
             ResolvedExpression::NamedVariable { variable } => match variable.data_type {
-                DataType::Struct(_) => self.lea(
+                DataType::Struct(_) | DataType::Array(..) => self.lea(
                     Reg::STACK {
                         offset: variable.stack_offset,
                     },
@@ -356,7 +859,7 @@ impl<'a> Visitor<&ResolvedExpression<'a>, Result<usize, Error>> for Generator {
             ResolvedExpression::Unary {
                 expression,
                 operation,
-                resulting_type,
+                ..
             } => match operation {
                 UnaryOps::NEG => {
                     expression.accept(self)?;
@@ -372,30 +875,15 @@ impl<'a> Visitor<&ResolvedExpression<'a>, Result<usize, Error>> for Generator {
                     expression.accept(self)?;
                     let reg = Reg::current();
                     self.cmp(Reg::IMMEDIATE(0), reg)?;
+                    Reg::set_size(4);
                     self.mov(Reg::IMMEDIATE(0), reg)?;
-                    let prev = Reg::set_size(1);
-                    let result = self.emit_sins("sete", reg);
-                    Reg::set_size(prev);
-                    result
+                    Reg::set_size(1);
+                    self.emit_sins("sete", reg)?;
+                    Reg::set_size(4);
+                    Ok(0)
                 }
-                UnaryOps::REF => match expression {
-                    ResolvedExpression::NamedVariable { variable } => self.lea(
-                        Reg::STACK {
-                            offset: variable.stack_offset,
-                        },
-                        Reg::current(),
-                    ),
-                    _ => panic!("should not happen!"),
-                },
-                UnaryOps::DEREF => match resulting_type {
-                    DataType::Struct(_) => expression.accept(self),
-                    _ => {
-                        expression.accept(self)?;
-                        let address = Reg::current().as_address();
-                        Reg::set_size(resulting_type.size());
-                        self.mov(address, Reg::current())
-                    }
-                },
+                UnaryOps::REF => self.generate_address(expression),
+                UnaryOps::DEREF => self.load(visitor),
                 UnaryOps::Cast(_) => expression.accept(self),
             },
             ResolvedExpression::BinaryExpression {
@@ -407,109 +895,79 @@ impl<'a> Visitor<&ResolvedExpression<'a>, Result<usize, Error>> for Generator {
                 if *operation == BinaryOps::AND || *operation == BinaryOps::OR {
                     return self.generate_and_or(visitor);
                 }
+                if *operation == BinaryOps::COMMA {
+                    // both sides use the same register, the right one wins
+                    lhs.accept(self)?;
+                    return rhs.accept(self);
+                }
 
                 let first_reg = Reg::current();
                 lhs.accept(self)?;
                 Reg::push();
                 rhs.accept(self)?;
                 let second_reg = Reg::pop();
+                // comparisons work on the operands but give an int
+                let data_type = lhs.data_type();
+                Reg::set_size(data_type.size());
+                let rhs_size = rhs.data_type().size();
+                self.binary_operation(*operation, data_type, rhs_size, second_reg, first_reg)?;
                 Reg::set_size(visitor.data_type().size());
-                match *operation {
-                    BinaryOps::ADD => self.add(second_reg, first_reg)?,
-                    BinaryOps::SUB => self.sub(second_reg, first_reg)?,
-                    BinaryOps::MUL => self.mul(second_reg, first_reg)?,
-                    BinaryOps::DIV => {
-                        self.mov(second_reg, Reg::RBX)?;
-                        self.mov(first_reg, Reg::RAX)?;
-                        self.cdq()?;
-                        self.emit_sins("idiv", Reg::RBX)?;
-                        self.mov(Reg::RAX, Reg::current())?
-                    }
-                    BinaryOps::MOD => {
-                        self.mov(second_reg, Reg::RBX)?;
-                        self.mov(first_reg, Reg::RAX)?;
-                        self.cdq()?;
-                        self.emit_sins("idiv", Reg::RBX)?;
-                        self.mov(Reg::RDX, Reg::current())?
-                    }
-                    BinaryOps::EQ => self.gen_cmp("sete", second_reg, first_reg)?,
-                    BinaryOps::NE => self.gen_cmp("setne", second_reg, first_reg)?,
-                    BinaryOps::LT => self.gen_cmp("setl", second_reg, first_reg)?,
-                    BinaryOps::GT => self.gen_cmp("setg", second_reg, first_reg)?,
-                    BinaryOps::LE => self.gen_cmp("setle", second_reg, first_reg)?,
-                    BinaryOps::GE => self.gen_cmp("setge", second_reg, first_reg)?,
-                    _ => panic!("Something went wrong"),
-                };
                 Ok(0)
             }
             ResolvedExpression::FunctionCall(call) => call.accept(self),
             ResolvedExpression::ArrayExpression(arr) => arr.accept(self),
-            ResolvedExpression::Indexing {
-                index,
-                operand,
-                data_type,
-            } => {
-                index.accept(self)?;
-                let index = Reg::push();
-                operand.accept(self)?;
-                let address = Reg::pop();
-
-                Reg::set_size(8);
-                self.mul(Reg::IMMEDIATE(visitor.data_type().size() as i64), index)?;
-                self.add(index, address)?;
-
-                match data_type {
-                    DataType::Struct(_) => {
-                        Reg::set_size(data_type.size());
-                        self.mov(address, Reg::current())
-                    }
-                    _ => {
-                        let address = address.as_address();
-                        Reg::set_size(data_type.size());
-                        self.mov(address, Reg::current())
-                    }
-                }
-            }
+            ResolvedExpression::Indexing { .. }
+            | ResolvedExpression::FieldAccess { .. }
+            | ResolvedExpression::ArrowAccess { .. } => self.load(visitor),
             ResolvedExpression::Assignment(assignment) => assignment.accept(self),
             // dont have to generate anything
             ResolvedExpression::TypeExpression(_) => Ok(0),
-            ResolvedExpression::FieldAccess {
-                field_offset,
-                data_type,
-                operand,
-            } => {
-                operand.accept(self)?;
-                match data_type {
-                    DataType::Struct(_) => {
-                        self.add(Reg::IMMEDIATE(*field_offset as i64), Reg::current())
-                    }
-                    _ => {
-                        Reg::set_size(data_type.size());
-                        self.mov(
-                            Reg::current().as_address().offset(*field_offset),
-                            Reg::current(),
-                        )
-                    }
-                }
-            }
             ResolvedExpression::StructExpresion(expr) => expr.accept(self),
             ResolvedExpression::SizeOf(value) => {
-                Reg::set_size(4);
+                Reg::set_size(8);
                 self.mov(Reg::IMMEDIATE(*value as i64), Reg::current())
             }
-            ResolvedExpression::ArrowAccess {
-                field_offset,
+            ResolvedExpression::Cast {
+                expression,
+                data_type: DataType::BOOL,
+            } if expression.data_type() != DataType::BOOL => {
+                expression.accept(self)?;
+                self.to_bool(expression.data_type().size(), Reg::current())
+            }
+            ResolvedExpression::Cast {
+                expression,
                 data_type,
-                operand,
             } => {
-                operand.accept(self)?;
+                expression.accept(self)?;
+                let from = expression.data_type();
+                if !from.is_scalar() || !data_type.is_scalar() {
+                    return Ok(0);
+                }
+                // a smaller value is extended, a larger one is cut off by
+                // only using the lower part of the register
+                if data_type.size() > from.size() {
+                    self.extend(from, Reg::current())?;
+                }
                 Reg::set_size(data_type.size());
-                self.mov(
-                    Reg::current().as_address().offset(*field_offset),
-                    Reg::current(),
-                )
+                Ok(0)
+            }
+            ResolvedExpression::Ternary {
+                condition,
+                then,
+                otherwise,
+                data_type,
+            } => {
+                let (otherwise_label, end) = self.generate_clause_names();
+                condition.accept(self)?;
+                self.cmp(Reg::IMMEDIATE(0), Reg::current())?;
+                self.je(&otherwise_label)?;
+                then.accept(self)?;
+                self.jmp(&end)?;
+                self.emit_label(&otherwise_label)?;
+                otherwise.accept(self)?;
+                Reg::set_size(data_type.size());
+                self.emit_label(&end)
             }
-            ResolvedExpression::Cast { expression, .. } => expression.accept(self),
         }
     }
 }
@@ -563,7 +1021,8 @@ impl<'a> Visitor<&ResolvedForStatement<'a>, Result<usize, Error>> for Generator
 
 impl<'a> Visitor<&ResolvedWhileStatement<'a>, Result<usize, Error>> for Generator {
     fn visit(&mut self, visitor: &ResolvedWhileStatement<'a>) -> Result<usize, Error> {
-        let (condition, end, _) = Self::generate_label_names(visitor.label_index);
+        // continue jumps to the condition
+        let (_, end, condition) = Self::generate_label_names(visitor.label_index);
         self.emit_label(&condition)?;
         visitor.condition.accept(self)?;
 
@@ -577,159 +1036,83 @@ impl<'a> Visitor<&ResolvedWhileStatement<'a>, Result<usize, Error>> for Generato
     }
 }
 
-impl<'a> Visitor<&ResolvedAssignment<'a>, Result<usize, Error>> for Generator {
-    fn visit(&mut self, visitor: &ResolvedAssignment<'a>) -> Result<usize, Error> {
-        match visitor {
-            ResolvedAssignment::StackAssignment {
-                variable,
-                expression,
-            } => match variable.data_type {
-                DataType::Struct(_) => {
-                    expression.accept(self)?;
-                    let from = Reg::push();
-                    let to = Reg::push();
-                    self.lea(
-                        Reg::STACK {
-                            offset: variable.stack_offset,
-                        },
-                        to,
-                    )?;
-                    let result = self.mov_bytes(from, to, variable.data_type.size());
-                    Reg::pop();
-                    Reg::pop();
-
-                    result
-                }
-                _ => {
-                    expression.accept(self)?;
-                    Reg::set_size(variable.data_type.size());
-                    self.mov(
-                        Reg::current(),
-                        Reg::STACK {
-                            offset: variable.stack_offset,
-                        },
-                    )
-                }
-            },
-            ResolvedAssignment::PtrAssignment {
-                data_type,
-                value,
-                address,
-            } => match data_type {
-                DataType::Struct(_) => {
-                    Reg::set_size(data_type.size());
-                    address.accept(self)?;
-                    let address = Reg::push();
-                    value.accept(self)?;
-                    let value = Reg::push();
-
-                    let result = self.mov_bytes(value, address, data_type.size());
-
-                    Reg::pop();
-                    Reg::pop();
-                    result
-                }
-                _ => {
-                    Reg::set_size(data_type.size());
-                    address.accept(self)?;
-                    let address = Reg::push().as_address();
-                    value.accept(self)?;
-                    let value = Reg::current();
-
-                    Reg::set_size(data_type.size());
-                    let result = self.mov(value, address);
-                    Reg::pop();
-                    result
-                }
-            },
-            ResolvedAssignment::ArrayAssignment {
-                data_type,
-                index,
-                value,
-                address,
-            } => match data_type {
-                DataType::Struct(_) => {
-                    value.accept(self)?;
-                    let value = Reg::push();
-
-                    address.accept(self)?;
-                    let address = Reg::push();
-
-                    index.accept(self)?;
-                    let index = Reg::current();
-
-                    Reg::set_size(8);
-                    self.mul(Reg::IMMEDIATE(visitor.data_type().size() as i64), index)?;
-                    self.add(index, address)?;
-
-                    let result = self.mov_bytes(value, address, data_type.size());
-
-                    Reg::pop();
-                    Reg::pop();
-
-                    result
-                }
-                _ => {
-                    value.accept(self)?;
-                    let value = Reg::push();
-
-                    address.accept(self)?;
-                    let address = Reg::push();
-
-                    index.accept(self)?;
-                    let index = Reg::current();
-
-                    self.mul(Reg::IMMEDIATE(visitor.data_type().size() as i64), index)?;
-                    Reg::set_size(8);
-                    self.add(index, address)?;
-                    Reg::set_size(visitor.data_type().size());
-                    let result = self.mov(value, address.as_address());
+impl<'a> Visitor<&ResolvedDoWhileStatement<'a>, Result<usize, Error>> for Generator {
+    fn visit(&mut self, visitor: &ResolvedDoWhileStatement<'a>) -> Result<usize, Error> {
+        let (body, end, condition) = Self::generate_label_names(visitor.label_index);
+        self.emit_label(&body)?;
+        visitor.body.accept(self)?;
 
-                    Reg::pop();
-                    Reg::pop();
-                    result
-                }
-            },
-            ResolvedAssignment::FieldAssignment {
-                field_offset,
-                data_type,
-                value,
-                address,
-            } => match data_type {
-                DataType::Struct(_) => {
-                    address.accept(self)?;
-                    let address = Reg::push();
+        self.emit_label(&condition)?;
+        visitor.condition.accept(self)?;
+        self.cmp(Reg::IMMEDIATE(0), Reg::current())?;
+        self.jne(&body)?;
 
-                    value.accept(self)?;
-                    let from = Reg::push();
+        self.emit_label(&end)
+    }
+}
 
-                    // add the offset
-                    Reg::set_size(8);
-                    self.add(Reg::IMMEDIATE(*field_offset as i64), address)?;
+impl<'a> Visitor<&ResolvedSwitchStatement<'a>, Result<usize, Error>> for Generator {
+    fn visit(&mut self, visitor: &ResolvedSwitchStatement<'a>) -> Result<usize, Error> {
+        let (_, end, _) = Self::generate_label_names(visitor.label_index);
+        let default = match visitor.default {
+            Some(label_index) => Self::generate_case_name(label_index),
+            None => end.clone(),
+        };
 
-                    Reg::set_size(visitor.data_type().size());
-                    let result = self.mov_bytes(from, address, data_type.size());
-                    Reg::pop();
-                    Reg::pop();
-                    result
-                }
-                _ => {
-                    address.accept(self)?;
-                    let address = Reg::push();
-                    value.accept(self)?;
-                    let value = Reg::current();
+        visitor.condition.accept(self)?;
+        self.extend(visitor.condition.data_type(), Reg::current())?;
+
+        let min = visitor.cases.iter().map(|(x, _)| *x).min().unwrap_or(0);
+        let max = visitor.cases.iter().map(|(x, _)| *x).max().unwrap_or(0);
+        // a table pays off if there are enough cases and few gaps between them
+        let count = visitor.cases.len() as i128;
+        let dense = count >= 4
+            && (max as i128 - min as i128) < 3 * count
+            && i32::try_from(min).is_ok()
+            && i32::try_from(max - min).is_ok();
+        if dense {
+            self.jump_table(visitor.label_index, &visitor.cases, (min, max), &default)?;
+        } else {
+            self.compare_chain(&visitor.cases, &default)?;
+        }
 
-                    // add the offset
-                    Reg::set_size(8);
-                    self.add(Reg::IMMEDIATE(*field_offset as i64), address)?;
+        visitor.body.accept(self)?;
+        self.emit_label(&end)
+    }
+}
 
-                    let address = address.as_address();
-                    Reg::set_size(visitor.data_type().size());
-                    let result = self.mov(value, address);
-                    Reg::pop();
-                    result
-                }
-            },
+impl<'a> Visitor<&ResolvedAssignment<'a>, Result<usize, Error>> for Generator {
+    fn visit(&mut self, visitor: &ResolvedAssignment<'a>) -> Result<usize, Error> {
+        let data_type = visitor.data_type();
+        match (data_type, visitor.target) {
+            (DataType::Struct(_) | DataType::Array(..), target) => {
+                visitor.value.accept(self)?;
+                let from = Reg::push();
+                self.generate_address(target)?;
+                let to = Reg::push();
+                let result = self.mov_bytes(from, to, data_type.size());
+                Reg::pop();
+                Reg::pop();
+                result
+            }
+            // a variable on the stack can be stored to without computing its
+            // address
+            (_, ResolvedExpression::NamedVariable { variable })
+                if matches!(variable.storage, StorageClass::Auto) =>
+            {
+                self.store(
+                    Reg::STACK {
+                        offset: variable.stack_offset,
+                    },
+                    data_type,
+                    visitor.value,
+                    visitor.operation,
+                )
+            }
+            (_, target) => {
+                self.generate_address(target)?;
+                self.store_at_current(data_type, visitor.value, visitor.operation)
+            }
         }
     }
 }
@@ -757,10 +1140,14 @@ impl<'a> Visitor<&ResolvedArrayExpression<'a>, Result<usize, Error>> for Generat
                 data_type,
                 stack_offset,
             } => {
+                // elements without an initialiser are zero
+                if let DataType::Array(..) = data_type {
+                    self.zero_stack(*stack_offset, data_type.size())?;
+                }
                 for expr in expressions {
                     expr.accept(self)?;
                 }
-                Reg::set_size(data_type.size());
+                Reg::set_size(8);
                 self.lea(
                     Reg::STACK {
                         offset: *stack_offset,
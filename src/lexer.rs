@@ -5,7 +5,9 @@ pub mod tokens;
 #[derive(Copy, Clone, Debug)]
 pub struct SrcLocation<'a> {
     pub(crate) src: &'a str,
+    pub(crate) file: &'a str,
     pub(crate) line: usize,
+    // the column the token starts at, counted from 1
     pub(crate) column: usize,
 }
 
@@ -20,81 +22,257 @@ pub type Token<'a> = (TokenKind, SrcLocation<'a>);
 pub mod Lexer {
 
     use super::{
-        tokens::{TokenKind, TOKEN_PATTERN},
+        tokens::{TokenKind, KEYWORDS, PUNCTUATORS},
         SrcLocation, Token,
     };
-    use regex::Regex;
+    use crate::error::Error;
 
-    pub fn tokenize<'a>(content: &'a str) -> Vec<Token<'a>> {
-        let patterns: Vec<(TokenKind, Regex)> = TOKEN_PATTERN
-            .iter()
-            .enumerate()
-            .map(|(index, pattern)| (TokenKind::from(index), Regex::new(pattern).unwrap()))
-            .collect();
+    /// what went wrong while scanning a token, the location is added by
+    /// `tokenize`
+    enum LexError {
+        UnknownCharacter,
+        UnterminatedString,
+        UnterminatedCharLiteral,
+        UnterminatedComment,
+    }
+
+    /// `file` is the name the tokens are reported in, until a line marker
+    /// `# 12 "file"` from the preprocessor changes it. Lexing goes on after
+    /// an error, so that all of them are reported at once.
+    pub fn tokenize<'a>(content: &'a str, file: &'a str) -> Result<Vec<Token<'a>>, Vec<Error<'a>>> {
+        scan(content, file, false)
+    }
+
+    /// the tokens for the preprocessor. Skipped groups may contain any text,
+    /// so what cannot be lexed becomes `TokenKind::OTHER` instead of an
+    /// error. The rest of it is reported when the expanded file is lexed.
+    pub fn preprocessing_tokens<'a>(content: &'a str, file: &'a str) -> Vec<Token<'a>> {
+        scan(content, file, true).unwrap_or_default()
+    }
 
+    fn scan<'a>(
+        content: &'a str,
+        file: &'a str,
+        preprocessing: bool,
+    ) -> Result<Vec<Token<'a>>, Vec<Error<'a>>> {
         let mut tokens = Vec::new();
+        let mut errors = Vec::new();
         let mut index = 0;
-        let mut last_index;
+        let mut file = file;
 
-        let mut column = 0;
+        let mut column = 1;
         let mut line_breaks = 1;
 
         loop {
-            last_index = index;
-            let next_token = next_token(&mut index, content, &patterns);
-            let token_string = &content[last_index..index];
-
-            for i in token_string.bytes() {
-                column += 1;
-                if i == b'\n' {
-                    line_breaks += 1;
-                    column = 0;
+            let end = skip_trivia(content, index);
+            advance(&content[index..end], &mut line_breaks, &mut column);
+            index = end;
+
+            if let Some((line, name, end)) = line_marker(content, index) {
+                line_breaks = line;
+                file = name;
+                column = 1;
+                index = end;
+                continue;
+            }
+
+            let start = index;
+            let start_line = line_breaks;
+            let start_column = column;
+            let next_token = next_token(&mut index, content);
+            let token_string = &content[start..index];
+            advance(token_string, &mut line_breaks, &mut column);
+
+            let location = SrcLocation {
+                src: token_string.trim(),
+                file,
+                line: start_line,
+                column: start_column,
+            };
+            match next_token {
+                Ok(TokenKind::EOF) => {
+                    tokens.push((TokenKind::EOF, location));
+                    break;
+                }
+                Ok(token) => tokens.push((token, location)),
+                Err(_) if preprocessing => tokens.push((TokenKind::OTHER, location)),
+                Err(LexError::UnknownCharacter) => {
+                    errors.push(Error::UnknownCharacter { location })
+                }
+                Err(LexError::UnterminatedString) => {
+                    errors.push(Error::UnterminatedString { location })
+                }
+                Err(LexError::UnterminatedCharLiteral) => {
+                    errors.push(Error::UnterminatedCharLiteral { location })
+                }
+                Err(LexError::UnterminatedComment) => errors.push(Error::UnterminatedComment {
+                    location: SrcLocation {
+                        src: &token_string[..2],
+                        ..location
+                    },
+                }),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(tokens)
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// moves the position behind `text`
+    fn advance(text: &str, line: &mut usize, column: &mut usize) {
+        for i in text.bytes() {
+            *column += 1;
+            if i == b'\n' {
+                *line += 1;
+                *column = 1;
+            }
+        }
+    }
+
+    /// skips whitespace and comments, returns the index of the next token
+    fn skip_trivia(content: &str, mut index: usize) -> usize {
+        loop {
+            let rest = &content[index..];
+            if rest.starts_with(|x: char| x.is_ascii_whitespace()) {
+                index += 1;
+            } else if rest.starts_with("//") {
+                index += rest.find('\n').unwrap_or(rest.len());
+            } else if let Some(body) = rest.strip_prefix("/*") {
+                match body.find("*/") {
+                    Some(end) => index += end + 4,
+                    // reported by `next_token`
+                    None => return index,
                 }
+            } else {
+                return index;
             }
+        }
+    }
 
-            let token = (
-                next_token,
-                SrcLocation {
-                    src: token_string.trim(),
-                    line: line_breaks,
-                    column: column,
-                },
-            );
-            tokens.push(token);
-
-            if next_token == TokenKind::EOF {
-                break;
+    /// true if the whitespace and comments between two tokens contain a
+    /// newline that is not inside of a `/* */` comment
+    pub fn ends_line(trivia: &str) -> bool {
+        let mut index = 0;
+        while index < trivia.len() {
+            let rest = &trivia[index..];
+            if rest.starts_with('\n') {
+                return true;
+            } else if rest.starts_with("//") {
+                index += rest.find('\n').unwrap_or(rest.len());
+            } else if let Some(body) = rest.strip_prefix("/*") {
+                index += body.find("*/").map_or(rest.len(), |end| end + 4);
+            } else {
+                index += 1;
             }
         }
-        tokens
+        false
     }
 
-    fn next_token(
-        index: &mut usize,
-        content: &str,
-        patterns: &Vec<(TokenKind, Regex)>,
-    ) -> TokenKind {
-        while *index < content.len() && content.as_bytes()[*index].is_ascii_whitespace() {
-            *index += 1;
+    /// reads a line marker `# 12 "file"` if there is one at `index`. It has
+    /// to be the first thing on its line. Returns the line number of the
+    /// following line, the file name and the index after the marker.
+    fn line_marker(content: &str, index: usize) -> Option<(usize, &str, usize)> {
+        let rest = &content[index..];
+        if !rest.starts_with('#') {
+            return None;
         }
-        if *index == content.len() {
-            return TokenKind::EOF;
+        let line_start = content[..index].rfind('\n').map(|x| x + 1).unwrap_or(0);
+        if !content[line_start..index].trim().is_empty() {
+            return None;
         }
 
-        let result = patterns
-            .iter()
-            .map(|(token, regex)| (token, regex.find_at(&content, *index)))
-            .filter_map(|(token, x)| match x {
-                Some(m) if m.start() == *index && m.len() > 0 => Some((token, m.end())),
-                Some(_) => None,
-                None => None,
-            })
-            .next();
-        if let Some((token, end)) = result {
-            *index = end;
-            return token.clone();
+        let end = rest.find('\n').map(|x| x + 1).unwrap_or(rest.len());
+        let marker = rest[..end].strip_prefix('#')?.trim_start();
+
+        let digits = marker.find(|x: char| !x.is_ascii_digit())?;
+        let line = marker[..digits].parse().ok()?;
+        let name = marker[digits..].trim_start().strip_prefix('"')?;
+        let name = &name[..name.find('"')?];
+        Some((line, name, index + end))
+    }
+
+    /// the length of the char or string literal at the start of `rest`,
+    /// escaped quotes do not end it. If the line ends first, the error holds
+    /// the length up to the end of the line.
+    fn quoted(rest: &str) -> Result<usize, usize> {
+        let quote = rest.as_bytes()[0];
+        let mut index = 1;
+        while index < rest.len() {
+            match rest.as_bytes()[index] {
+                b'\\' => index += 1,
+                b'\n' => return Err(index),
+                x if x == quote => return Ok(index + 1),
+                _ => (),
+            }
+            index += 1;
         }
+        Err(rest.len())
+    }
+
+    /// scans the token at `index` and moves `index` behind it. Every token
+    /// is decided by its first characters, so the file is read only once.
+    fn next_token(index: &mut usize, content: &str) -> Result<TokenKind, LexError> {
+        let rest = &content[*index..];
+        let first = match rest.chars().next() {
+            Some(x) => x,
+            None => return Ok(TokenKind::EOF),
+        };
 
-        panic!("was not able to tokinize {content}");
+        let (token, length) = if first.is_ascii_digit() {
+            // suffixes and prefixes like `0x` belong to the number, the
+            // parser checks the spelling
+            let length = rest
+                .find(|x: char| !(x.is_ascii_alphanumeric() || x == '_'))
+                .unwrap_or(rest.len());
+            (TokenKind::INTLITERAL, length)
+        } else if first.is_alphanumeric() || first == '_' {
+            // the whole word is read first, so `integer` is not `int` + `eger`
+            let length = rest
+                .find(|x: char| !(x.is_alphanumeric() || x == '_'))
+                .unwrap_or(rest.len());
+            match KEYWORDS
+                .iter()
+                .find(|(keyword, _)| *keyword == &rest[..length])
+            {
+                Some((_, token)) => (*token, length),
+                None => (TokenKind::IDENT, length),
+            }
+        } else if first == '\'' {
+            match quoted(rest) {
+                Ok(length) => (TokenKind::CHARLITERAL, length),
+                Err(length) => {
+                    *index += length;
+                    return Err(LexError::UnterminatedCharLiteral);
+                }
+            }
+        } else if first == '"' {
+            match quoted(rest) {
+                Ok(length) => (TokenKind::STRINGLIT, length),
+                Err(length) => {
+                    *index += length;
+                    return Err(LexError::UnterminatedString);
+                }
+            }
+        } else if rest.starts_with("/*") {
+            // `skip_trivia` leaves only comments without an end
+            *index = content.len();
+            return Err(LexError::UnterminatedComment);
+        } else {
+            match PUNCTUATORS
+                .iter()
+                .find(|(spelling, _)| rest.starts_with(spelling))
+            {
+                Some((spelling, token)) => (*token, spelling.len()),
+                None => {
+                    *index += first.len_utf8();
+                    return Err(LexError::UnknownCharacter);
+                }
+            }
+        };
+        *index += length;
+        Ok(token)
     }
 }
@@ -27,6 +27,8 @@ pub enum TokenKind {
     LOGNEG,
     IF,
     WHILE,
+    DO,
+    GOTO,
     FOR,
     ELSE,
     BREAK,
@@ -45,122 +47,127 @@ pub enum TokenKind {
     DOT,
     SIZEOF,
     HASHTAG,
-    DEFINE,
-    INCLUDE,
     TYPEOF,
     ESCAPELINE,
+    BITOR,
+    XOR,
+    SHL,
+    SHR,
+    QUESTION,
+    COLON,
+    ADDASSIGN,
+    SUBASSIGN,
+    MULASSIGN,
+    DIVASSIGN,
+    MODASSIGN,
+    ANDASSIGN,
+    ORASSIGN,
+    XORASSIGN,
+    SHLASSIGN,
+    SHRASSIGN,
+    INC,
+    DEC,
+    SWITCH,
+    ENUM,
+    UNION,
+    STATIC,
+    CONST,
+    STATICASSERT,
+    ALIGNOF,
+    SHORT,
+    SIGNED,
+    UNSIGNED,
+    BOOL,
+    CASE,
+    DEFAULT,
     IDENT,
+    /// text that is no token, only lexed by the preprocessor. It is an error
+    /// once it ends up in the program.
+    OTHER,
     EOF,
 }
 
-impl TokenKind {
-    pub fn from(value: usize) -> TokenKind {
-        match value {
-            0 => TokenKind::VOID,
-            1 => TokenKind::INT,
-            2 => TokenKind::SEMIC,
-            3 => TokenKind::LCURL,
-            4 => TokenKind::RCURL,
-            5 => TokenKind::LPAREN,
-            6 => TokenKind::RPAREN,
-            7 => TokenKind::RETURN,
-            8 => TokenKind::INTLITERAL,
-            9 => TokenKind::ARROW,
-            10 => TokenKind::ADD,
-            11 => TokenKind::SUB,
-            12 => TokenKind::MUL,
-            13 => TokenKind::DIV,
-            14 => TokenKind::AND,
-            15 => TokenKind::REF,
-            16 => TokenKind::OR,
-            17 => TokenKind::EQ,
-            18 => TokenKind::NE,
-            19 => TokenKind::LE,
-            20 => TokenKind::GE,
-            21 => TokenKind::LT,
-            22 => TokenKind::GT,
-            23 => TokenKind::ASSIGN,
-            24 => TokenKind::LOGNEG,
-            25 => TokenKind::IF,
-            26 => TokenKind::WHILE,
-            27 => TokenKind::FOR,
-            28 => TokenKind::ELSE,
-            29 => TokenKind::BREAK,
-            30 => TokenKind::CONTINUE,
-            31 => TokenKind::COMMA,
-            32 => TokenKind::MOD,
-            33 => TokenKind::CHAR,
-            34 => TokenKind::CHARLITERAL,
-            35 => TokenKind::LONG,
-            36 => TokenKind::LBRACE,
-            37 => TokenKind::RBRACE,
-            38 => TokenKind::STRINGLIT,
-            39 => TokenKind::COMPLEMENT,
-            40 => TokenKind::TYPEDEF,
-            41 => TokenKind::STRUCT,
-            42 => TokenKind::DOT,
-            43 => TokenKind::SIZEOF,
-            44 => TokenKind::HASHTAG,
-            45 => TokenKind::DEFINE,
-            46 => TokenKind::INCLUDE,
-            47 => TokenKind::TYPEOF,
-            48 => TokenKind::ESCAPELINE,
-            49 => TokenKind::IDENT,
-            50 => TokenKind::EOF,
-            _ => todo!(),
-        }
-    }
-}
+/// the words that are lexed as keywords instead of identifiers
+pub static KEYWORDS: &[(&str, TokenKind)] = &[
+    ("void", TokenKind::VOID),
+    ("int", TokenKind::INT),
+    ("return", TokenKind::RETURN),
+    ("if", TokenKind::IF),
+    ("while", TokenKind::WHILE),
+    ("do", TokenKind::DO),
+    ("goto", TokenKind::GOTO),
+    ("switch", TokenKind::SWITCH),
+    ("case", TokenKind::CASE),
+    ("default", TokenKind::DEFAULT),
+    ("for", TokenKind::FOR),
+    ("else", TokenKind::ELSE),
+    ("break", TokenKind::BREAK),
+    ("continue", TokenKind::CONTINUE),
+    ("char", TokenKind::CHAR),
+    ("long", TokenKind::LONG),
+    ("short", TokenKind::SHORT),
+    ("signed", TokenKind::SIGNED),
+    ("unsigned", TokenKind::UNSIGNED),
+    ("_Bool", TokenKind::BOOL),
+    ("typedef", TokenKind::TYPEDEF),
+    ("struct", TokenKind::STRUCT),
+    ("enum", TokenKind::ENUM),
+    ("union", TokenKind::UNION),
+    ("static", TokenKind::STATIC),
+    ("const", TokenKind::CONST),
+    ("_Static_assert", TokenKind::STATICASSERT),
+    ("_Alignof", TokenKind::ALIGNOF),
+    ("sizeof", TokenKind::SIZEOF),
+    ("typeof", TokenKind::TYPEOF),
+];
 
-pub static TOKEN_PATTERN: &'static [&'static str] = &[
-    "void",
-    "int",
-    ";",
-    "\\{",
-    "\\}",
-    "\\(",
-    "\\)",
-    "return",
-    "[0-9]+",
-    "\\->",
-    "\\+",
-    "\\-",
-    "\\*",
-    "\\/",
-    "\\&\\&",
-    "\\&",
-    "\\|\\|",
-    "\\=\\=",
-    "\\!\\=",
-    "<\\=",
-    ">\\=",
-    "<",
-    ">",
-    "\\=",
-    "\\!",
-    "if",
-    "while",
-    "for",
-    "else",
-    "break",
-    "continue",
-    "\\,",
-    "\\%",
-    "char",
-    "(\'.\')|(\'\\\\.\')",
-    "long",
-    "\\[",
-    "\\]",
-    "\"[^\"]*\"",
-    "~",
-    "typedef",
-    "struct",
-    "\\.",
-    "sizeof",
-    "#",
-    "define",
-    "include",
-    "typeof",
-    "\\\\\\\n",
-    "\\w+",
-];
\ No newline at end of file
+/// the operators and separators, longer ones before their prefixes
+pub static PUNCTUATORS: &[(&str, TokenKind)] = &[
+    ("<<=", TokenKind::SHLASSIGN),
+    (">>=", TokenKind::SHRASSIGN),
+    ("->", TokenKind::ARROW),
+    ("++", TokenKind::INC),
+    ("--", TokenKind::DEC),
+    ("+=", TokenKind::ADDASSIGN),
+    ("-=", TokenKind::SUBASSIGN),
+    ("*=", TokenKind::MULASSIGN),
+    ("/=", TokenKind::DIVASSIGN),
+    ("%=", TokenKind::MODASSIGN),
+    ("&=", TokenKind::ANDASSIGN),
+    ("|=", TokenKind::ORASSIGN),
+    ("^=", TokenKind::XORASSIGN),
+    ("&&", TokenKind::AND),
+    ("||", TokenKind::OR),
+    ("==", TokenKind::EQ),
+    ("!=", TokenKind::NE),
+    ("<=", TokenKind::LE),
+    (">=", TokenKind::GE),
+    ("<<", TokenKind::SHL),
+    (">>", TokenKind::SHR),
+    ("\\\n", TokenKind::ESCAPELINE),
+    (";", TokenKind::SEMIC),
+    ("{", TokenKind::LCURL),
+    ("}", TokenKind::RCURL),
+    ("(", TokenKind::LPAREN),
+    (")", TokenKind::RPAREN),
+    ("+", TokenKind::ADD),
+    ("-", TokenKind::SUB),
+    ("*", TokenKind::MUL),
+    ("/", TokenKind::DIV),
+    ("&", TokenKind::REF),
+    ("<", TokenKind::LT),
+    (">", TokenKind::GT),
+    ("=", TokenKind::ASSIGN),
+    ("!", TokenKind::LOGNEG),
+    (",", TokenKind::COMMA),
+    ("%", TokenKind::MOD),
+    ("[", TokenKind::LBRACE),
+    ("]", TokenKind::RBRACE),
+    ("~", TokenKind::COMPLEMENT),
+    (".", TokenKind::DOT),
+    ("#", TokenKind::HASHTAG),
+    ("|", TokenKind::BITOR),
+    ("^", TokenKind::XOR),
+    ("?", TokenKind::QUESTION),
+    (":", TokenKind::COLON),
+];
@@ -2,15 +2,17 @@ pub mod error;
 pub mod generator;
 pub mod lexer;
 pub mod parser;
+pub mod preprocessor;
 pub mod scope_builder;
 pub mod visitor;
 
-use std::{env, process::ExitCode};
+use std::{env, path::PathBuf, process::ExitCode, time::Instant};
 
 use bumpalo::Bump;
 
 use lexer::Lexer;
 use parser::Parser;
+use preprocessor::Preprocessor;
 use scope_builder::ScopeBuilder;
 use visitor::Visitable;
 
@@ -19,15 +21,79 @@ use crate::generator::Generator;
 fn main() -> ExitCode {
     let args: Vec<_> = env::args().into_iter().collect();
     if args.len() < 3 {
-        println!("Usage: ./rust-compiler 'code.c' 'output.s' [-ast] [-tokens]");
+        println!(
+            "Usage: ./rust-compiler 'code.c' 'output.s' [-ast] [-tokens] [-lex] [-I dir] [-isystem dir]"
+        );
         return ExitCode::FAILURE;
     }
     let code = &args[1];
     let output = &args[2];
 
+    let mut include_paths = Vec::new();
+    // searched after the `-I` paths, before the headers of the compiler
+    let mut system_paths = Vec::new();
+    let mut lex_only = false;
+    let mut flags = args[3..].iter();
+    while let Some(flag) = flags.next() {
+        if flag == "-lex" {
+            lex_only = true;
+            continue;
+        }
+        if flag == "-isystem" {
+            match flags.next() {
+                Some(path) => system_paths.push(PathBuf::from(path)),
+                None => {
+                    println!("Missing directory after -isystem");
+                    return ExitCode::FAILURE;
+                }
+            }
+            continue;
+        }
+        match flag.strip_prefix("-I") {
+            Some("") => match flags.next() {
+                Some(path) => include_paths.push(PathBuf::from(path)),
+                None => {
+                    println!("Missing directory after -I");
+                    return ExitCode::FAILURE;
+                }
+            },
+            Some(path) => include_paths.push(PathBuf::from(path)),
+            None => (),
+        }
+    }
+    include_paths.append(&mut system_paths);
+
     let bump = Bump::new();
     let content = std::fs::read_to_string(code).expect("was not able to open file!");
-    let tokens = bump.alloc(Lexer::tokenize(&content));
+    let content = bump.alloc_str(&content);
+    // only times the lexer on the file, without the preprocessor and the
+    // later phases
+    if lex_only {
+        let start = Instant::now();
+        let tokens = Lexer::tokenize(content, code);
+        println!("lexed in {:.6}s", start.elapsed().as_secs_f64());
+        return match tokens {
+            Ok(_) => ExitCode::SUCCESS,
+            Err(_) => ExitCode::FAILURE,
+        };
+    }
+    let mut preprocessor = Preprocessor::new(&bump, include_paths);
+    let content = match preprocessor.expand(bump.alloc_str(code), content) {
+        Ok(x) => x,
+        Err(e) => {
+            println!("Error while preprocessing: {:#?}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let tokens = match Lexer::tokenize(content, code) {
+        Ok(x) => bump.alloc(x),
+        Err(errors) => {
+            for e in errors {
+                println!("Error while lexing: {:#?}", e);
+            }
+            return ExitCode::FAILURE;
+        }
+    };
     let mut parser = Parser::new(tokens, &bump);
     let program = parser.program();
     let mut scope_builder = ScopeBuilder::new(&bump);
@@ -5,13 +5,12 @@ use crate::{
     lexer::{tokens::TokenKind, SrcLocation, Token},
 };
 
-use self::ast::expression::Expression;
-
 pub mod ast;
+pub mod literal;
 
 pub struct Parser<'a> {
     pub(crate) bump: &'a Bump,
-    pub(crate) assignee: Option<&'a Expression<'a>>,
+    pub(crate) type_names: Vec<&'a str>,
     tokens: &'a [Token<'a>],
     index: usize,
 }
@@ -22,7 +21,7 @@ impl<'a> Parser<'a> {
             tokens,
             bump,
             index: 0,
-            assignee: None,
+            type_names: Vec::new(),
         }
     }
 
@@ -71,6 +70,10 @@ impl<'a> Parser<'a> {
         self.tokens[self.index]
     }
 
+    pub fn is_type_name(&self, name: &str) -> bool {
+        self.type_names.contains(&name)
+    }
+
     pub fn anchor(&self) -> usize {
         self.index
     }
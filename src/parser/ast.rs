@@ -11,6 +11,8 @@ pub mod compound_statement;
 pub mod if_statement;
 pub mod for_statement;
 pub mod while_statement;
+pub mod switch_statement;
+pub mod do_while_statement;
 pub mod function;
 
 
@@ -27,4 +29,6 @@ pub use compound_statement::*;
 pub use if_statement::*;
 pub use for_statement::*;
 pub use while_statement::*;
+pub use switch_statement::*;
+pub use do_while_statement::*;
 pub use function::*;
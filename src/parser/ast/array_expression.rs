@@ -1,4 +1,9 @@
-use crate::{error::Error, lexer::tokens::TokenKind, parser::Parser, visitor::Visitable};
+use crate::{
+    error::Error,
+    lexer::tokens::TokenKind,
+    parser::{literal, Parser},
+    visitor::Visitable,
+};
 
 use super::expression::Expression;
 
@@ -8,7 +13,7 @@ pub enum ArrayExpression<'a> {
         expressions: Vec<&'a Expression<'a>>,
     },
     StringLiteral {
-        string: &'a str,
+        string: &'a [u8],
     },
 }
 
@@ -18,8 +23,10 @@ impl<'a> Parser<'a> {
     pub fn array_expression(&mut self) -> Result<&'a ArrayExpression<'a>, Error<'a>> {
         Ok(self.bump.alloc(match self.peek() {
             TokenKind::STRINGLIT => {
-                let string = self.expect(TokenKind::STRINGLIT)?.string();
-                ArrayExpression::StringLiteral { string }
+                let string = literal::string_literal(self.expect(TokenKind::STRINGLIT)?)?;
+                ArrayExpression::StringLiteral {
+                    string: self.bump.alloc_slice_copy(&string),
+                }
             }
             _ => {
                 let mut expressions = Vec::new();
@@ -1,89 +1,96 @@
-use crate::{error::Error, lexer::tokens::TokenKind, parser::Parser, visitor::Visitable};
+use crate::{
+    error::Error,
+    lexer::{tokens::TokenKind, SrcLocation},
+    parser::Parser,
+    visitor::Visitable,
+};
 
-use super::{expression::Expression, UnaryOps};
+use super::{expression::Expression, BinaryOps};
 
+/// how the assigned value is combined with the old one
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AssignOperation {
+    /// `a = b`
+    Assign,
+    /// `a += b` or `++a`, the result is the new value
+    Compound(BinaryOps),
+    /// `a++`, the result is the old value
+    Postfix(BinaryOps),
+}
+
+/// stores `value` in `target`. Any expression is accepted as target here,
+/// the scope builder checks that it is an lvalue.
 #[derive(Debug)]
-pub enum Assignment<'a> {
-    VariableAssignment {
-        name: &'a str,
-        expression: &'a Expression<'a>,
-    },
-    PtrAssignment {
-        value: &'a Expression<'a>,
-        address: &'a Expression<'a>,
-    },
-    ArrayAssignment {
-        index: &'a Expression<'a>,
-        value: &'a Expression<'a>,
-        address: &'a Expression<'a>,
-    },
-    FieldAssignment {
-        name: &'a str,
-        value: &'a Expression<'a>,
-        address: &'a Expression<'a>,
-    },
+pub struct Assignment<'a> {
+    pub(crate) target: &'a Expression<'a>,
+    pub(crate) value: &'a Expression<'a>,
+    pub(crate) operation: AssignOperation,
+    // where the target starts
+    pub(crate) location: SrcLocation<'a>,
 }
 
 impl Visitable for Assignment<'_> {}
 
 impl<'a> Parser<'a> {
-    pub fn assignment(&mut self) -> Result<&'a Assignment<'a>, Error<'a>> {
-        self.expect(TokenKind::ASSIGN)?;
-        let expr = self.assignee.unwrap();
-        Ok(self.bump.alloc(match expr {
-            Expression::NamedVariable { name } => {
-                let expression = self.expression()?;
-                Assignment::VariableAssignment {
-                    name: name,
-                    expression: expression,
-                }
-            }
-            Expression::Indexing { index, operand } => {
-                let expression = self.expression()?;
-                Assignment::ArrayAssignment {
-                    index: index,
-                    value: expression,
-                    address: operand,
-                }
-            }
-            Expression::Unary {
-                expression: address,
-                operation,
-            } => match operation {
-                UnaryOps::DEREF => {
-                    let expression = self.expression()?;
-                    Assignment::PtrAssignment {
-                        address: address,
-                        value: expression,
-                    }
-                }
-                _ => {
-                    return Err(Error::UnableToAssign {
-                        location: self.current().1,
-                    })
-                }
-            },
-            Expression::FieldAccess { name, operand } => {
-                let expression = self.expression()?;
-                Assignment::FieldAssignment {
-                    name: name,
-                    address: operand,
-                    value: expression,
-                }
-            }
-            Expression::ArrowAccess { name, operand } => {
-                let expression = self.expression()?;
-                Assignment::FieldAssignment {
-                    name: name,
-                    address: operand,
-                    value: expression,
-                }
-            }
-            _ => {
-                return Err(Error::UnableToAssign {
-                    location: self.current().1,
+    /// reads the assignment operator and the value that is assigned to
+    /// `target`
+    pub fn assignment(
+        &mut self,
+        target: &'a Expression<'a>,
+        location: SrcLocation<'a>,
+    ) -> Result<&'a Assignment<'a>, Error<'a>> {
+        let (token, operator) = self.next();
+        let operation = match token {
+            TokenKind::ASSIGN => AssignOperation::Assign,
+            TokenKind::ADDASSIGN => AssignOperation::Compound(BinaryOps::ADD),
+            TokenKind::SUBASSIGN => AssignOperation::Compound(BinaryOps::SUB),
+            TokenKind::MULASSIGN => AssignOperation::Compound(BinaryOps::MUL),
+            TokenKind::DIVASSIGN => AssignOperation::Compound(BinaryOps::DIV),
+            TokenKind::MODASSIGN => AssignOperation::Compound(BinaryOps::MOD),
+            TokenKind::ANDASSIGN => AssignOperation::Compound(BinaryOps::BITAND),
+            TokenKind::ORASSIGN => AssignOperation::Compound(BinaryOps::BITOR),
+            TokenKind::XORASSIGN => AssignOperation::Compound(BinaryOps::XOR),
+            TokenKind::SHLASSIGN => AssignOperation::Compound(BinaryOps::SHL),
+            TokenKind::SHRASSIGN => AssignOperation::Compound(BinaryOps::SHR),
+            found => {
+                return Err(Error::UnexpectedToken {
+                    expected: TokenKind::ASSIGN,
+                    found,
+                    location: operator,
                 })
             }
+        };
+        let value = self.expression()?;
+        Ok(self.alloc(Assignment {
+            target,
+            value,
+            operation,
+            location,
+        }))
+    }
+
+    /// `++a` is `a += 1` and `a++` the same with the old value as result
+    pub fn increment(
+        &mut self,
+        target: &'a Expression<'a>,
+        token: TokenKind,
+        postfix: bool,
+        location: SrcLocation<'a>,
+    ) -> Result<&'a Assignment<'a>, Error<'a>> {
+        let operation = match token {
+            TokenKind::INC => BinaryOps::ADD,
+            _ => BinaryOps::SUB,
+        };
+        let operation = if postfix {
+            AssignOperation::Postfix(operation)
+        } else {
+            AssignOperation::Compound(operation)
+        };
+        Ok(self.alloc(Assignment {
+            target,
+            value: self.alloc(Expression::IntLiteral(1)),
+            operation,
+            location,
         }))
     }
 }
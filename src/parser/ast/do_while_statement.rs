@@ -0,0 +1,25 @@
+use crate::{error::Error, lexer::tokens::TokenKind, parser::Parser, visitor::Visitable};
+
+use super::{compound_statement::Compound, expression::Expression};
+
+/// runs the body once before the condition is checked
+#[derive(Debug)]
+pub struct DoWhileStatement<'a> {
+    pub(crate) body: &'a Compound<'a>,
+    pub(crate) condition: &'a Expression<'a>,
+}
+
+impl Visitable for DoWhileStatement<'_> {}
+
+impl<'a> Parser<'a> {
+    pub fn do_while_statement(&mut self) -> Result<&'a DoWhileStatement<'a>, Error<'a>> {
+        self.expect(TokenKind::DO)?;
+        let body = self.compound_statement()?;
+        self.expect(TokenKind::WHILE)?;
+        self.expect(TokenKind::LPAREN)?;
+        let condition = self.comma_expression()?;
+        self.expect(TokenKind::RPAREN)?;
+        self.expect(TokenKind::SEMIC)?;
+        Ok(self.alloc(DoWhileStatement { body, condition }))
+    }
+}
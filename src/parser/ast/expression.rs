@@ -1,4 +1,9 @@
-use crate::{error::Error, lexer::tokens::TokenKind, parser::Parser, visitor::Visitable};
+use crate::{
+    error::Error,
+    lexer::{tokens::TokenKind, SrcLocation},
+    parser::{literal, Parser},
+    visitor::Visitable,
+};
 
 use super::{
     array_expression::ArrayExpression, assignment::Assignment, function_call::FunctionCall,
@@ -20,6 +25,60 @@ pub enum BinaryOps {
     GT,
     LE,
     GE,
+    BITAND,
+    BITOR,
+    XOR,
+    SHL,
+    SHR,
+    COMMA,
+}
+
+impl BinaryOps {
+    /// applies the operation to two constants. `&&` and `||` do not short
+    /// circuit here, the caller has to. None if the result is undefined.
+    pub fn evaluate(&self, lhs: i64, rhs: i64) -> Option<i64> {
+        Some(match self {
+            BinaryOps::ADD => lhs.wrapping_add(rhs),
+            BinaryOps::SUB => lhs.wrapping_sub(rhs),
+            BinaryOps::MUL => lhs.wrapping_mul(rhs),
+            BinaryOps::DIV | BinaryOps::MOD if rhs == 0 => return None,
+            BinaryOps::DIV => lhs.wrapping_div(rhs),
+            BinaryOps::MOD => lhs.wrapping_rem(rhs),
+            BinaryOps::AND => (lhs != 0 && rhs != 0) as i64,
+            BinaryOps::OR => (lhs != 0 || rhs != 0) as i64,
+            BinaryOps::EQ => (lhs == rhs) as i64,
+            BinaryOps::NE => (lhs != rhs) as i64,
+            BinaryOps::LT => (lhs < rhs) as i64,
+            BinaryOps::GT => (lhs > rhs) as i64,
+            BinaryOps::LE => (lhs <= rhs) as i64,
+            BinaryOps::GE => (lhs >= rhs) as i64,
+            BinaryOps::BITAND => lhs & rhs,
+            BinaryOps::BITOR => lhs | rhs,
+            BinaryOps::XOR => lhs ^ rhs,
+            BinaryOps::SHL | BinaryOps::SHR if !(0..64).contains(&rhs) => return None,
+            BinaryOps::SHL => lhs.wrapping_shl(rhs as u32),
+            BinaryOps::SHR => lhs >> rhs,
+            BinaryOps::COMMA => rhs,
+        })
+    }
+
+    /// like `evaluate`, but division, shifts and comparisons treat both
+    /// sides as unsigned
+    pub fn evaluate_unsigned(&self, lhs: i64, rhs: i64) -> Option<i64> {
+        let (lhs, rhs) = (lhs as u64, rhs as u64);
+        Some(match self {
+            BinaryOps::DIV | BinaryOps::MOD if rhs == 0 => return None,
+            BinaryOps::DIV => (lhs / rhs) as i64,
+            BinaryOps::MOD => (lhs % rhs) as i64,
+            BinaryOps::LT => (lhs < rhs) as i64,
+            BinaryOps::GT => (lhs > rhs) as i64,
+            BinaryOps::LE => (lhs <= rhs) as i64,
+            BinaryOps::GE => (lhs >= rhs) as i64,
+            BinaryOps::SHR if rhs >= 64 => return None,
+            BinaryOps::SHR => (lhs >> rhs) as i64,
+            _ => return self.evaluate(lhs as i64, rhs as i64),
+        })
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -32,9 +91,24 @@ pub enum UnaryOps<'a> {
     Cast(&'a TypeExpression<'a>),
 }
 
+impl UnaryOps<'_> {
+    /// applies the operation to a constant, casts are left to the caller
+    pub fn evaluate(&self, value: i64) -> Option<i64> {
+        match self {
+            UnaryOps::NEG => Some(value.wrapping_neg()),
+            UnaryOps::LOGNEG => Some((value == 0) as i64),
+            UnaryOps::COMPLEMENT => Some(!value),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum Expression<'a> {
     IntLiteral(i32),
+    UIntLiteral(u32),
+    LongLiteral(i64),
+    ULongLiteral(u64),
     CharLiteral(u8),
     FunctionCall(&'a FunctionCall<'a>),
     ArrayExpression(&'a ArrayExpression<'a>),
@@ -42,6 +116,7 @@ pub enum Expression<'a> {
     Assignment(&'a Assignment<'a>),
     TypeExpression(&'a TypeExpression<'a>),
     SizeOf(&'a Expression<'a>),
+    AlignOf(&'a Expression<'a>),
     FieldAccess {
         name: &'a str,
         operand: &'a Expression<'a>,
@@ -66,6 +141,11 @@ pub enum Expression<'a> {
         rhs: &'a Expression<'a>,
         operation: BinaryOps,
     },
+    Ternary {
+        condition: &'a Expression<'a>,
+        then: &'a Expression<'a>,
+        otherwise: &'a Expression<'a>,
+    },
 }
 
 impl<'a> Visitable for Expression<'a> {}
@@ -74,6 +154,12 @@ impl<'a> Parser<'a> {
     pub fn expression(&mut self) -> Result<&'a Expression<'a>, Error<'a>> {
         Ok(self.bump.alloc(Expression::parse_expressions(self)?))
     }
+
+    /// an expression that may contain the comma operator. Function arguments
+    /// and initialisers use `expression`, because there the comma separates.
+    pub fn comma_expression(&mut self) -> Result<&'a Expression<'a>, Error<'a>> {
+        Ok(self.bump.alloc(Expression::parse_comma(self)?))
+    }
 }
 
 impl<'a> Expression<'a> {
@@ -88,38 +174,15 @@ impl<'a> Expression<'a> {
             }
             TokenKind::STRINGLIT => Ok(Self::ArrayExpression(parser.array_expression()?)),
             TokenKind::INTLITERAL => {
-                let value: i32 = parser
-                    .expect(TokenKind::INTLITERAL)?
-                    .string()
-                    .trim()
-                    .parse()
-                    .expect("was not able to parse int literal");
-                Ok(Self::IntLiteral(value))
+                literal::integer_literal(parser.expect(TokenKind::INTLITERAL)?)
             }
-            TokenKind::CHARLITERAL => {
-                let string = parser.expect(TokenKind::CHARLITERAL)?.string();
-                if string.len() > 3 {
-                    let string = &string[1..3];
-                    return match string {
-                        "\\n" => Ok(Self::CharLiteral(b'\n')),
-                        "\\t" => Ok(Self::CharLiteral(b'\t')),
-                        _ => {
-                            println!("{}", string);
-                            let value: u8 = string[1..2].as_bytes().first().unwrap().clone();
-                            Ok(Self::CharLiteral(value))
-                        }
-                    };
-                }
-                let value: u8 = string[1..2].as_bytes().first().unwrap().clone();
-                Ok(Self::CharLiteral(value))
+            TokenKind::CHARLITERAL => literal::char_literal(parser.expect(TokenKind::CHARLITERAL)?),
+            // typedef names have to be known here, otherwise casts like
+            // `(Foo*)x` would be parsed as a multiplication
+            TokenKind::IDENT if parser.is_type_name(parser.current().1.string()) => {
+                Ok(Self::TypeExpression(parser.type_expression()?))
             }
             TokenKind::IDENT => {
-                // if parser.scope.contains::<Rc<Struct>>(&name) {
-                //     Ok(Self::TypeExpression(parser.parse()?))
-                // } else if parser.scope.contains::<TypeDefinition>(&name) {
-                //     Ok(Self::TypeExpression(parser.parse()?))
-                // } else {
-                //     let name = parser.expect(TokenKind::IDENT)?.to_string();
                 let name = parser.expect(TokenKind::IDENT)?.string();
                 match parser.peek() {
                     TokenKind::LPAREN => Ok(Self::FunctionCall(parser.function_call()?)),
@@ -128,16 +191,19 @@ impl<'a> Expression<'a> {
             }
             TokenKind::LPAREN => {
                 parser.expect(TokenKind::LPAREN)?;
-                let result = Self::parse_expressions(parser);
+                let result = Self::parse_comma(parser);
                 parser.expect(TokenKind::RPAREN)?;
                 result
             }
-            TokenKind::SIZEOF => {
+            token @ (TokenKind::SIZEOF | TokenKind::ALIGNOF) => {
                 parser.next();
                 parser.expect(TokenKind::LPAREN)?;
-                let expression = Self::parse_expressions(parser)?;
+                let expression = parser.bump.alloc(Self::parse_expressions(parser)?);
                 parser.expect(TokenKind::RPAREN)?;
-                Ok(Self::SizeOf(parser.bump.alloc(expression)))
+                match token {
+                    TokenKind::SIZEOF => Ok(Self::SizeOf(expression)),
+                    _ => Ok(Self::AlignOf(expression)),
+                }
             }
             _ => Ok(Self::TypeExpression(
                 parser.bump.alloc(parser.type_expression()?),
@@ -151,7 +217,7 @@ impl<'a> Expression<'a> {
     ) -> Result<Self, Error<'a>> {
         while parser.peek() == TokenKind::LBRACE {
             parser.next();
-            let expression = parser.expression()?;
+            let expression = parser.comma_expression()?;
             parser.expect(TokenKind::RBRACE)?;
             operand = Self::Indexing {
                 index: parser.bump.alloc(expression),
@@ -192,21 +258,42 @@ impl<'a> Expression<'a> {
     }
 
     fn parse_postfix(parser: &mut Parser<'a>) -> Result<Self, Error<'a>> {
+        let location = parser.current().1;
         let mut result = Self::parse_literal(parser)?;
         while parser.peek() == TokenKind::LBRACE
             || parser.peek() == TokenKind::DOT
             || parser.peek() == TokenKind::ARROW
+            || parser.peek() == TokenKind::INC
+            || parser.peek() == TokenKind::DEC
         {
             result = match parser.peek() {
                 TokenKind::LBRACE => Self::parse_indexing(result, parser),
                 TokenKind::DOT => Self::parse_field_access(result, parser),
                 TokenKind::ARROW => Self::parse_arrow_access(result, parser),
+                TokenKind::INC | TokenKind::DEC => {
+                    let token = parser.next_kind();
+                    Self::parse_increment(result, token, true, location, parser)
+                }
                 _ => Ok(result),
             }?;
         }
         Ok(result)
     }
 
+    /// `++operand`, `--operand` or with `postfix` `operand++`, `operand--`
+    fn parse_increment(
+        operand: Expression<'a>,
+        token: TokenKind,
+        postfix: bool,
+        location: SrcLocation<'a>,
+        parser: &mut Parser<'a>,
+    ) -> Result<Self, Error<'a>> {
+        let target = parser.bump.alloc(operand);
+        Ok(Self::Assignment(
+            parser.increment(target, token, postfix, location)?,
+        ))
+    }
+
     fn parse_unary(parser: &mut Parser<'a>) -> Result<Self, Error<'a>> {
         let (token, location) = parser.next();
         let e = Self::parse_factor(parser)?;
@@ -236,6 +323,11 @@ impl<'a> Expression<'a> {
             | TokenKind::MUL
             | TokenKind::REF
             | TokenKind::COMPLEMENT => Self::parse_unary(parser),
+            TokenKind::INC | TokenKind::DEC => {
+                let (token, location) = parser.next();
+                let operand = Self::parse_factor(parser)?;
+                Self::parse_increment(operand, token, false, location, parser)
+            }
             // only literal left to parse
             _ => Self::parse_postfix(parser),
         };
@@ -343,29 +435,97 @@ impl<'a> Expression<'a> {
                     rhs,
                     operation: BinaryOps::GE,
                 },
+                TokenKind::REF => Self::BinaryExpression {
+                    lhs,
+                    rhs,
+                    operation: BinaryOps::BITAND,
+                },
+                TokenKind::BITOR => Self::BinaryExpression {
+                    lhs,
+                    rhs,
+                    operation: BinaryOps::BITOR,
+                },
+                TokenKind::XOR => Self::BinaryExpression {
+                    lhs,
+                    rhs,
+                    operation: BinaryOps::XOR,
+                },
+                TokenKind::SHL => Self::BinaryExpression {
+                    lhs,
+                    rhs,
+                    operation: BinaryOps::SHL,
+                },
+                TokenKind::SHR => Self::BinaryExpression {
+                    lhs,
+                    rhs,
+                    operation: BinaryOps::SHR,
+                },
                 _ => panic!("should never happen!"),
             }
         }
         Ok(expression)
     }
 
-    pub fn parse_expressions(parser: &mut Parser<'a>) -> Result<Self, Error<'a>> {
+    fn parse_conditional(parser: &mut Parser<'a>) -> Result<Self, Error<'a>> {
+        // from the lowest to the highest precedence
         let operations = [
             vec![TokenKind::OR],
             vec![TokenKind::AND],
+            vec![TokenKind::BITOR],
+            vec![TokenKind::XOR],
+            vec![TokenKind::REF],
             vec![TokenKind::EQ, TokenKind::NE],
             vec![TokenKind::GT, TokenKind::GE, TokenKind::LT, TokenKind::LE],
+            vec![TokenKind::SHL, TokenKind::SHR],
             vec![TokenKind::ADD, TokenKind::SUB],
             vec![TokenKind::MUL, TokenKind::DIV, TokenKind::MOD],
         ];
-        let result = Self::parse_binary(parser, &operations, 0)?;
+        let condition = Self::parse_binary(parser, &operations, 0)?;
+        if parser.peek() != TokenKind::QUESTION {
+            return Ok(condition);
+        }
+        parser.next();
+        let then = Self::parse_comma(parser)?;
+        parser.expect(TokenKind::COLON)?;
+        let otherwise = Self::parse_conditional(parser)?;
+        Ok(Self::Ternary {
+            condition: parser.bump.alloc(condition),
+            then: parser.bump.alloc(then),
+            otherwise: parser.bump.alloc(otherwise),
+        })
+    }
+
+    fn parse_comma(parser: &mut Parser<'a>) -> Result<Self, Error<'a>> {
+        let mut expression = Self::parse_expressions(parser)?;
+        while parser.peek() == TokenKind::COMMA {
+            parser.next();
+            let rhs = Self::parse_expressions(parser)?;
+            expression = Self::BinaryExpression {
+                lhs: parser.bump.alloc(expression),
+                rhs: parser.bump.alloc(rhs),
+                operation: BinaryOps::COMMA,
+            };
+        }
+        Ok(expression)
+    }
+
+    pub fn parse_expressions(parser: &mut Parser<'a>) -> Result<Self, Error<'a>> {
+        let location = parser.current().1;
+        let result = Self::parse_conditional(parser)?;
         match parser.peek() {
-            TokenKind::ASSIGN => {
-                parser.assignee = Some(parser.bump.alloc(result));
-                let assignment = parser.assignment()?;
-                let result = Ok(Self::Assignment(parser.bump.alloc(assignment)));
-                parser.assignee = None;
-                result
+            TokenKind::ASSIGN
+            | TokenKind::ADDASSIGN
+            | TokenKind::SUBASSIGN
+            | TokenKind::MULASSIGN
+            | TokenKind::DIVASSIGN
+            | TokenKind::MODASSIGN
+            | TokenKind::ANDASSIGN
+            | TokenKind::ORASSIGN
+            | TokenKind::XORASSIGN
+            | TokenKind::SHLASSIGN
+            | TokenKind::SHRASSIGN => {
+                let target = parser.bump.alloc(result);
+                Ok(Self::Assignment(parser.assignment(target, location)?))
             }
             _ => Ok(result),
         }
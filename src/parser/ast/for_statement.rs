@@ -20,7 +20,7 @@ impl<'a> Parser<'a> {
 
         let condition;
         if self.peek() != TokenKind::SEMIC {
-            condition = self.expression()?;
+            condition = self.comma_expression()?;
         } else {
             condition = self.alloc(Expression::IntLiteral(1));
         }
@@ -28,7 +28,7 @@ impl<'a> Parser<'a> {
 
         let post;
         if self.peek() != TokenKind::RPAREN {
-            post = Some(self.expression()?);
+            post = Some(self.comma_expression()?);
         } else {
             post = None;
         }
@@ -14,6 +14,8 @@ impl Visitable for Function<'_> {}
 
 impl<'a> Parser<'a> {
     pub fn function(&mut self) -> Result<&'a Function<'a>, Error<'a>> {
+        // functions are always visible to other files
+        self.specifiers();
         let return_type = self.type_expression()?;
         let name = self.expect(TokenKind::IDENT)?.string();
 
@@ -23,6 +25,13 @@ impl<'a> Parser<'a> {
         while self.peek() != TokenKind::RPAREN {
             let type_expression = self.type_expression()?;
             let name = self.expect(TokenKind::IDENT)?.string();
+            // an array parameter is a pointer to its first element
+            let type_expression = match self.array_suffix(type_expression)? {
+                TypeExpression::Array { element, .. } => {
+                    self.alloc(TypeExpression::Pointer(element))
+                }
+                x => x,
+            };
             parameter.push((type_expression, name));
             if self.peek() == TokenKind::RPAREN {
                 break;
@@ -22,7 +22,7 @@ impl<'a> Parser<'a> {
     pub fn if_statement(&mut self) -> Result<&'a IfStatement<'a>, Error<'a>> {
         self.expect(TokenKind::IF)?;
         self.expect(TokenKind::LPAREN)?;
-        let condition = self.expression()?;
+        let condition = self.comma_expression()?;
         self.expect(TokenKind::RPAREN)?;
         let statements = self.compound_statement()?;
         let mut else_part: ElsePart = ElsePart::None;
@@ -24,10 +24,9 @@ impl<'a> Parser<'a> {
         let mut declarations: Vec<Decalrations> = Vec::new();
         while self.peek() != TokenKind::EOF {
             declarations.push(match self.peek() {
-                TokenKind::TYPEDEF | TokenKind::STRUCT => {
-                    Decalrations::Statement(self.statement()?)
-                }
-                _ => Decalrations::Function(self.function()?),
+                TokenKind::TYPEDEF => Decalrations::Statement(self.statement()?),
+                _ if self.is_function() => Decalrations::Function(self.function()?),
+                _ => Decalrations::Statement(self.statement()?),
             })
         }
         self.expect(TokenKind::EOF)?;
@@ -35,4 +34,16 @@ impl<'a> Parser<'a> {
             declarations: declarations,
         }))
     }
+
+    /// true if the next declaration is a function, so its name is followed
+    /// by the parameters
+    fn is_function(&mut self) -> bool {
+        let anchor = self.anchor();
+        self.specifiers();
+        let is_function = self.type_expression().is_ok()
+            && self.next_kind() == TokenKind::IDENT
+            && self.peek() == TokenKind::LPAREN;
+        self.reset(anchor);
+        is_function
+    }
 }
@@ -1,9 +1,15 @@
-use crate::{error::Error, lexer::tokens::TokenKind, parser::Parser, visitor::Visitable};
+use crate::{
+    error::Error,
+    lexer::{tokens::TokenKind, SrcLocation},
+    parser::Parser,
+    visitor::Visitable,
+};
 
 use super::{
-    compound_statement::Compound, expression::Expression, for_statement::ForStatement,
-    if_statement::IfStatement, type_definition::TypeDefinition, while_statement::WhileStatement,
-    Assignment, TypeExpression,
+    compound_statement::Compound, do_while_statement::DoWhileStatement, expression::Expression,
+    for_statement::ForStatement, if_statement::IfStatement, switch_statement::SwitchStatement,
+    type_definition::TypeDefinition, while_statement::WhileStatement, AssignOperation, Assignment,
+    TypeExpression,
 };
 
 #[derive(Debug)]
@@ -14,11 +20,37 @@ pub enum Statement<'a> {
     IfStatement(&'a IfStatement<'a>),
     ForStatement(&'a ForStatement<'a>),
     WhileStatement(&'a WhileStatement<'a>),
+    SwitchStatement(&'a SwitchStatement<'a>),
+    DoWhileStatement(&'a DoWhileStatement<'a>),
+    Case {
+        value: &'a Expression<'a>,
+        location: SrcLocation<'a>,
+    },
+    Default {
+        location: SrcLocation<'a>,
+    },
+    Label {
+        name: &'a str,
+        location: SrcLocation<'a>,
+    },
+    Goto {
+        name: &'a str,
+        location: SrcLocation<'a>,
+    },
+    StaticAssert {
+        condition: &'a Expression<'a>,
+        message: Option<&'a str>,
+        location: SrcLocation<'a>,
+    },
     TypeDefinition(&'a TypeDefinition<'a>),
+    /// `is_const` is set if the variable itself is const, `const char* p`
+    /// only points to const chars
     VariableDeclaration {
         name: &'a str,
         expression: &'a TypeExpression<'a>,
         assignment: Option<&'a Assignment<'a>>,
+        is_static: bool,
+        is_const: bool,
     },
     Conitnue,
     Break,
@@ -28,6 +60,19 @@ pub enum Statement<'a> {
 impl Visitable for Statement<'_> {}
 
 impl<'a> Parser<'a> {
+    /// reads `static` and `const` in front of a declaration
+    pub fn specifiers(&mut self) -> (bool, bool) {
+        let (mut is_static, mut is_const) = (false, false);
+        loop {
+            match self.peek() {
+                TokenKind::STATIC => is_static = true,
+                TokenKind::CONST => is_const = true,
+                _ => return (is_static, is_const),
+            }
+            self.next();
+        }
+    }
+
     pub fn statement(&mut self) -> Result<&'a Statement<'a>, Error<'a>> {
         let result = match self.peek() {
             TokenKind::CONTINUE => {
@@ -44,7 +89,7 @@ impl<'a> Parser<'a> {
                     self.expect(TokenKind::SEMIC)?;
                     return Ok(self.alloc(Statement::Return(None)));
                 }
-                let expression = self.expression()?;
+                let expression = self.comma_expression()?;
                 Statement::Return(Some(expression))
             }
             TokenKind::IF => {
@@ -59,22 +104,84 @@ impl<'a> Parser<'a> {
                 let statement = self.while_statement()?;
                 return Ok(self.alloc(Statement::WhileStatement(statement)));
             }
+            TokenKind::STATICASSERT => {
+                let location = self.next().1;
+                self.expect(TokenKind::LPAREN)?;
+                let condition = self.expression()?;
+                let mut message = None;
+                if self.peek() == TokenKind::COMMA {
+                    self.next();
+                    message = Some(self.expect(TokenKind::STRINGLIT)?.src);
+                }
+                self.expect(TokenKind::RPAREN)?;
+                Statement::StaticAssert {
+                    condition,
+                    message,
+                    location,
+                }
+            }
+            TokenKind::DO => {
+                let statement = self.do_while_statement()?;
+                return Ok(self.alloc(Statement::DoWhileStatement(statement)));
+            }
+            TokenKind::GOTO => {
+                self.next();
+                let location = self.expect(TokenKind::IDENT)?;
+                Statement::Goto {
+                    name: location.string(),
+                    location,
+                }
+            }
+            TokenKind::IDENT if self.ahead(1) == TokenKind::COLON => {
+                let location = self.expect(TokenKind::IDENT)?;
+                self.expect(TokenKind::COLON)?;
+                return Ok(self.alloc(Statement::Label {
+                    name: location.string(),
+                    location,
+                }));
+            }
+            TokenKind::SWITCH => {
+                let statement = self.switch_statement()?;
+                return Ok(self.alloc(Statement::SwitchStatement(statement)));
+            }
+            // labels are followed by a colon instead of a semicolon
+            TokenKind::CASE => {
+                let location = self.next().1;
+                let value = self.expression()?;
+                self.expect(TokenKind::COLON)?;
+                return Ok(self.alloc(Statement::Case { value, location }));
+            }
+            TokenKind::DEFAULT => {
+                let location = self.next().1;
+                self.expect(TokenKind::COLON)?;
+                return Ok(self.alloc(Statement::Default { location }));
+            }
             TokenKind::INT
             | TokenKind::CHAR
             | TokenKind::LONG
+            | TokenKind::SHORT
+            | TokenKind::SIGNED
+            | TokenKind::UNSIGNED
+            | TokenKind::BOOL
             | TokenKind::VOID
             | TokenKind::STRUCT
+            | TokenKind::UNION
+            | TokenKind::ENUM
             | TokenKind::TYPEOF
+            | TokenKind::STATIC
+            | TokenKind::CONST
             | TokenKind::IDENT => {
+                let (is_static, is_const) = self.specifiers();
                 let anchor = self.anchor();
 
-                let mut expression = self.type_expression()?;
+                let (mut expression, qualified) = self.qualified_type()?;
+                // a leading const belongs to the type a pointer points to
+                let is_const =
+                    qualified || is_const && !matches!(expression, TypeExpression::Pointer(_));
                 let name = self.expect(TokenKind::IDENT);
 
-                if self.peek() == TokenKind::LBRACE {
-                    self.next();
-                    expression = self.alloc(TypeExpression::Pointer(expression));
-                    self.expect(TokenKind::RBRACE)?;
+                if name.is_ok() {
+                    expression = self.array_suffix(expression)?;
                 }
 
                 match name {
@@ -82,9 +189,13 @@ impl<'a> Parser<'a> {
                         let mut assignment = None;
                         if self.peek() == TokenKind::ASSIGN {
                             self.next();
-                            let result = Assignment::VariableAssignment {
-                                name: name.string(),
-                                expression: self.expression()?,
+                            let result = Assignment {
+                                target: self.alloc(Expression::NamedVariable {
+                                    name: name.string(),
+                                }),
+                                value: self.expression()?,
+                                operation: AssignOperation::Assign,
+                                location: name,
                             };
                             assignment = Some(&*self.alloc(result));
                         }
@@ -92,11 +203,13 @@ impl<'a> Parser<'a> {
                             name: name.string(),
                             expression,
                             assignment,
+                            is_static,
+                            is_const,
                         }
                     }
                     Err(_) => {
                         self.reset(anchor);
-                        Statement::SingleExpression(self.expression()?)
+                        Statement::SingleExpression(self.comma_expression()?)
                     }
                 }
             }
@@ -110,7 +223,7 @@ impl<'a> Parser<'a> {
                 return Ok(self.alloc(Statement::Compound(list)));
             }
             _ => {
-                let expr = self.expression()?;
+                let expr = self.comma_expression()?;
                 Statement::SingleExpression(expr)
             }
         };
@@ -0,0 +1,23 @@
+use crate::{error::Error, lexer::tokens::TokenKind, parser::Parser, visitor::Visitable};
+
+use super::{compound_statement::Compound, expression::Expression};
+
+/// the `case` and `default` labels are statements in `body`
+#[derive(Debug)]
+pub struct SwitchStatement<'a> {
+    pub(crate) condition: &'a Expression<'a>,
+    pub(crate) body: &'a Compound<'a>,
+}
+
+impl Visitable for SwitchStatement<'_> {}
+
+impl<'a> Parser<'a> {
+    pub fn switch_statement(&mut self) -> Result<&'a SwitchStatement<'a>, Error<'a>> {
+        self.expect(TokenKind::SWITCH)?;
+        self.expect(TokenKind::LPAREN)?;
+        let condition = self.comma_expression()?;
+        self.expect(TokenKind::RPAREN)?;
+        let body = self.compound_statement()?;
+        Ok(self.alloc(SwitchStatement { condition, body }))
+    }
+}
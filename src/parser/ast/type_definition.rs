@@ -16,6 +16,8 @@ impl<'a> Parser<'a> {
 
         let expression = self.type_expression()?;
         let name = self.expect(TokenKind::IDENT)?.string();
+        let expression = self.array_suffix(expression)?;
+        self.type_names.push(name);
 
         Ok(self.alloc(TypeDefinition { expression, name }))
     }
@@ -1,48 +1,132 @@
-use crate::{error::Error, lexer::tokens::TokenKind, parser::Parser, visitor::Visitable};
+use crate::{
+    error::Error,
+    lexer::{tokens::TokenKind, SrcLocation},
+    parser::Parser,
+    visitor::Visitable,
+};
 
 use super::expression::Expression;
 
 #[derive(Debug)]
 pub enum TypeExpression<'a> {
     Primitive(TokenKind),
+    /// `kind` is one of CHAR, SHORT, INT, LONG and BOOL
+    Integer {
+        kind: TokenKind,
+        unsigned: bool,
+    },
     Typeof(&'a Expression<'a>),
     Named(&'a str),
+    /// anonymous structs and unions have no name
     Struct {
-        name: &'a str,
+        name: Option<&'a str>,
         fields: Vec<(&'a str, &'a TypeExpression<'a>)>,
+        is_union: bool,
     },
     NamedStruct(&'a str),
+    /// enumerators without a value are one larger than the one before
+    Enum {
+        name: Option<&'a str>,
+        enumerators: Vec<(&'a str, Option<&'a Expression<'a>>)>,
+    },
+    NamedEnum(&'a str),
     Pointer(&'a TypeExpression<'a>),
+    /// the length is left out in `int a[] = {1, 2}`
+    Array {
+        element: &'a TypeExpression<'a>,
+        length: Option<&'a Expression<'a>>,
+    },
 }
 
 impl<'a> Visitable for TypeExpression<'a> {}
 
 impl<'a> Parser<'a> {
     pub fn type_expression(&mut self) -> Result<&'a TypeExpression<'a>, Error<'a>> {
+        Ok(self.qualified_type()?.0)
+    }
+
+    /// reads a type, the flag is set if the type itself is const and not
+    /// only what it points to
+    pub fn qualified_type(&mut self) -> Result<(&'a TypeExpression<'a>, bool), Error<'a>> {
+        let mut is_const = self.const_qualifiers();
         let mut type_expression = match self.next() {
-            (TokenKind::STRUCT, _) => {
-                let name = self.expect(TokenKind::IDENT)?.string();
-                // the final name should be: struct 'name'
-                let final_name = self.alloc(String::from("struct "));
-                final_name.push_str(name);
-                match self.peek() {
-                    TokenKind::LCURL => {
+            (token @ (TokenKind::STRUCT | TokenKind::UNION), _) => {
+                let is_union = token == TokenKind::UNION;
+                let mut name = None;
+                if self.peek() == TokenKind::IDENT {
+                    // the final name should be: struct 'name' or union 'name'
+                    let final_name = match is_union {
+                        true => self.alloc(String::from("union ")),
+                        false => self.alloc(String::from("struct ")),
+                    };
+                    final_name.push_str(self.expect(TokenKind::IDENT)?.string());
+                    name = Some(&**final_name);
+                }
+                match (self.peek(), name) {
+                    (TokenKind::LCURL, _) => {
                         self.expect(TokenKind::LCURL)?;
                         let mut fields = Vec::new();
                         while self.peek() != TokenKind::RCURL {
                             let type_expression = self.type_expression()?;
                             let name = self.expect(TokenKind::IDENT)?.string();
+                            let type_expression = self.array_suffix(type_expression)?;
                             fields.push((name, type_expression));
                             self.expect(TokenKind::SEMIC)?;
                         }
                         self.next();
 
                         TypeExpression::Struct {
-                            name: final_name,
+                            name,
                             fields: fields,
+                            is_union,
                         }
                     }
-                    _ => TypeExpression::NamedStruct(final_name),
+                    (_, Some(name)) => TypeExpression::NamedStruct(name),
+                    (found, None) => {
+                        return Err(Error::UnexpectedToken {
+                            expected: TokenKind::LCURL,
+                            found,
+                            location: self.current().1,
+                        })
+                    }
+                }
+            }
+            (TokenKind::ENUM, _) => {
+                let mut name = None;
+                if self.peek() == TokenKind::IDENT {
+                    let final_name = self.alloc(String::from("enum "));
+                    final_name.push_str(self.expect(TokenKind::IDENT)?.string());
+                    name = Some(&**final_name);
+                }
+                match (self.peek(), name) {
+                    (TokenKind::LCURL, _) => {
+                        self.expect(TokenKind::LCURL)?;
+                        let mut enumerators = Vec::new();
+                        while self.peek() != TokenKind::RCURL {
+                            let name = self.expect(TokenKind::IDENT)?.string();
+                            let mut value = None;
+                            if self.peek() == TokenKind::ASSIGN {
+                                self.next();
+                                value = Some(self.expression()?);
+                            }
+                            enumerators.push((name, value));
+                            if self.peek() == TokenKind::RCURL {
+                                break;
+                            }
+                            self.expect(TokenKind::COMMA)?;
+                        }
+                        self.next();
+
+                        TypeExpression::Enum { name, enumerators }
+                    }
+                    (_, Some(name)) => TypeExpression::NamedEnum(name),
+                    (found, None) => {
+                        return Err(Error::UnexpectedToken {
+                            expected: TokenKind::LCURL,
+                            found,
+                            location: self.current().1,
+                        })
+                    }
                 }
             }
             (TokenKind::IDENT, location) => TypeExpression::Named(location.src),
@@ -52,14 +136,111 @@ impl<'a> Parser<'a> {
                 self.expect(TokenKind::RPAREN)?;
                 TypeExpression::Typeof(self.alloc(expression))
             }
+            (
+                token @ (TokenKind::CHAR
+                | TokenKind::SHORT
+                | TokenKind::INT
+                | TokenKind::LONG
+                | TokenKind::SIGNED
+                | TokenKind::UNSIGNED
+                | TokenKind::BOOL),
+                location,
+            ) => self.integer_type(token, location)?,
             (x, _) => TypeExpression::Primitive(x),
         };
 
+        is_const |= self.const_qualifiers();
+
         while self.peek() == TokenKind::MUL {
             self.next();
+            is_const = self.const_qualifiers();
             let allocation = &*self.alloc(type_expression);
             type_expression = TypeExpression::Pointer(allocation);
         }
-        Ok(self.alloc(type_expression))
+        Ok((self.alloc(type_expression), is_const))
+    }
+
+    /// skips `const`, true if there was one
+    fn const_qualifiers(&mut self) -> bool {
+        let mut is_const = false;
+        while self.peek() == TokenKind::CONST {
+            self.next();
+            is_const = true;
+        }
+        is_const
+    }
+
+    /// reads the words of an integer type in any order, like `unsigned
+    /// long int`. `long long` is the same as `long`.
+    fn integer_type(
+        &mut self,
+        mut token: TokenKind,
+        mut location: SrcLocation<'a>,
+    ) -> Result<TypeExpression<'a>, Error<'a>> {
+        let mut kind = None;
+        let mut unsigned = None;
+        loop {
+            match (token, kind) {
+                (TokenKind::SIGNED | TokenKind::UNSIGNED, _)
+                    if unsigned.is_none() && kind != Some(TokenKind::BOOL) =>
+                {
+                    unsigned = Some(token == TokenKind::UNSIGNED)
+                }
+                (TokenKind::INT, None | Some(TokenKind::SHORT) | Some(TokenKind::LONG)) => {
+                    kind = kind.or(Some(TokenKind::INT))
+                }
+                (TokenKind::LONG, None | Some(TokenKind::INT) | Some(TokenKind::LONG))
+                | (TokenKind::SHORT, None | Some(TokenKind::INT)) => kind = Some(token),
+                (TokenKind::CHAR, None) => kind = Some(token),
+                (TokenKind::BOOL, None) if unsigned.is_none() => kind = Some(token),
+                (found, _) => {
+                    return Err(Error::UnexpectedToken {
+                        expected: TokenKind::INT,
+                        found,
+                        location,
+                    })
+                }
+            }
+            match self.peek() {
+                TokenKind::CHAR
+                | TokenKind::SHORT
+                | TokenKind::INT
+                | TokenKind::LONG
+                | TokenKind::SIGNED
+                | TokenKind::UNSIGNED
+                | TokenKind::BOOL => (token, location) = self.next(),
+                _ => break,
+            }
+        }
+        Ok(TypeExpression::Integer {
+            kind: kind.unwrap_or(TokenKind::INT),
+            unsigned: unsigned.unwrap_or(false),
+        })
+    }
+
+    /// reads the `[N]` after a declared name. `int m[3][4]` is an array of
+    /// three arrays of four ints.
+    pub fn array_suffix(
+        &mut self,
+        element: &'a TypeExpression<'a>,
+    ) -> Result<&'a TypeExpression<'a>, Error<'a>> {
+        let mut lengths = Vec::new();
+        while self.peek() == TokenKind::LBRACE {
+            self.next();
+            let mut length = None;
+            if self.peek() != TokenKind::RBRACE {
+                length = Some(self.expression()?);
+            }
+            self.expect(TokenKind::RBRACE)?;
+            lengths.push(length);
+        }
+        let mut type_expression = element;
+        for length in lengths.into_iter().rev() {
+            type_expression = self.alloc(TypeExpression::Array {
+                element: type_expression,
+                length,
+            });
+        }
+        Ok(type_expression)
     }
 }
@@ -14,7 +14,7 @@ impl<'a> Parser<'a> {
     pub fn while_statement(&mut self) -> Result<&'a WhileStatement<'a>, Error<'a>> {
         self.expect(TokenKind::WHILE)?;
         self.expect(TokenKind::LPAREN)?;
-        let condition = self.expression()?;
+        let condition = self.comma_expression()?;
         self.expect(TokenKind::RPAREN)?;
         let body = self.compound_statement()?;
         Ok(self.alloc(WhileStatement { condition, body }))
@@ -0,0 +1,123 @@
+use crate::{error::Error, lexer::SrcLocation};
+
+use super::ast::Expression;
+
+/// reads an integer literal like `42`, `0x2a`, `052`, `0b101010` or `42UL`.
+/// Like in C the literal gets the first type its value fits in, a decimal
+/// one is signed unless it has a `U` suffix, the others may also become
+/// unsigned.
+pub fn integer_literal<'a>(location: SrcLocation<'a>) -> Result<Expression<'a>, Error<'a>> {
+    let spelling = location.src;
+    let invalid = Error::InvalidIntegerLiteral { location };
+
+    let (radix, digits) = match spelling.get(..2) {
+        Some("0x" | "0X") => (16, &spelling[2..]),
+        Some("0b" | "0B") => (2, &spelling[2..]),
+        _ if spelling.starts_with('0') => (8, spelling),
+        _ => (10, spelling),
+    };
+    let end = digits
+        .find(|x: char| !x.is_digit(radix))
+        .unwrap_or(digits.len());
+    let (digits, suffix) = digits.split_at(end);
+    if digits.is_empty() {
+        return Err(invalid);
+    }
+
+    let (unsigned, long) = match suffix {
+        "" => (false, false),
+        "u" | "U" => (true, false),
+        "l" | "L" | "ll" | "LL" => (false, true),
+        "ul" | "uL" | "Ul" | "UL" | "lu" | "lU" | "Lu" | "LU" => (true, true),
+        "ull" | "uLL" | "Ull" | "ULL" | "llu" | "llU" | "LLu" | "LLU" => (true, true),
+        _ => return Err(invalid),
+    };
+
+    let value = match u64::from_str_radix(digits, radix) {
+        Ok(x) => x,
+        Err(_) => return Err(Error::IntegerLiteralOutOfRange { location }),
+    };
+    let decimal = radix == 10;
+    Ok(match (unsigned, long) {
+        (false, false) if value <= i32::MAX as u64 => Expression::IntLiteral(value as i32),
+        (false, false) if !decimal && value <= u32::MAX as u64 => {
+            Expression::UIntLiteral(value as u32)
+        }
+        (true, false) if value <= u32::MAX as u64 => Expression::UIntLiteral(value as u32),
+        (false, _) if value <= i64::MAX as u64 => Expression::LongLiteral(value as i64),
+        // only unsigned literals may use the sign bit of a decimal long
+        (false, _) if decimal => return Err(Error::IntegerLiteralOutOfRange { location }),
+        _ => Expression::ULongLiteral(value),
+    })
+}
+
+/// reads a character literal like `'a'`, `'\n'` or `'\x41'`
+pub fn char_literal<'a>(location: SrcLocation<'a>) -> Result<Expression<'a>, Error<'a>> {
+    let body = &location.src[1..location.src.len() - 1];
+    match unescape(body, location)?.as_slice() {
+        [value] => Ok(Expression::CharLiteral(*value)),
+        _ => Err(Error::InvalidCharLiteral { location }),
+    }
+}
+
+/// the bytes of a string literal without its quotes
+pub fn string_literal<'a>(location: SrcLocation<'a>) -> Result<Vec<u8>, Error<'a>> {
+    unescape(&location.src[1..location.src.len() - 1], location)
+}
+
+/// replaces the escape sequences of a char or string literal
+fn unescape<'a>(body: &str, location: SrcLocation<'a>) -> Result<Vec<u8>, Error<'a>> {
+    let mut bytes = Vec::new();
+    let mut rest = body.as_bytes();
+    while let Some((&first, tail)) = rest.split_first() {
+        rest = tail;
+        if first != b'\\' {
+            bytes.push(first);
+            continue;
+        }
+        let (&escaped, tail) = match rest.split_first() {
+            Some(x) => x,
+            None => return Err(Error::InvalidEscapeSequence { location }),
+        };
+        rest = tail;
+        let value = match escaped {
+            // a backslash at the end of a line continues the literal
+            b'\n' => continue,
+            b'n' => b'\n',
+            b't' => b'\t',
+            b'r' => b'\r',
+            b'a' => 0x07,
+            b'b' => 0x08,
+            b'f' => 0x0c,
+            b'v' => 0x0b,
+            b'\\' | b'\'' | b'"' | b'?' => escaped,
+            b'0'..=b'7' => {
+                // up to three octal digits
+                let mut value = (escaped - b'0') as u32;
+                let mut count = 1;
+                while count < 3 && matches!(rest.first(), Some(b'0'..=b'7')) {
+                    value = value * 8 + (rest[0] - b'0') as u32;
+                    rest = &rest[1..];
+                    count += 1;
+                }
+                u8::try_from(value).map_err(|_| Error::InvalidEscapeSequence { location })?
+            }
+            b'x' => {
+                // as many hex digits as there are
+                let count = rest.iter().take_while(|x| x.is_ascii_hexdigit()).count();
+                if count == 0 {
+                    return Err(Error::InvalidEscapeSequence { location });
+                }
+                let digits = std::str::from_utf8(&rest[..count]).unwrap();
+                rest = &rest[count..];
+                u64::from_str_radix(digits, 16)
+                    .ok()
+                    .and_then(|x| u8::try_from(x).ok())
+                    .ok_or(Error::InvalidEscapeSequence { location })?
+            }
+            _ => return Err(Error::InvalidEscapeSequence { location }),
+        };
+        bytes.push(value);
+    }
+    Ok(bytes)
+}
@@ -1,250 +1,662 @@
-use crate::lexer::{tokens::Token, Lexer, };
+pub mod macros;
+
+use std::{
+    cell::Cell,
+    path::{Path, PathBuf},
+};
+
+use bumpalo::Bump;
+
+use self::macros::Macro;
+use crate::{
+    error::Error,
+    lexer::{tokens::TokenKind, Lexer, SrcLocation, Token},
+    parser::{
+        ast::{BinaryOps, Expression, UnaryOps},
+        Parser,
+    },
+};
+
+/// the headers in the source tree, used if none are next to the executable
+static SOURCE_INCLUDE_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/include");
+
+/// the headers that come with the compiler, searched after every `-I` and
+/// `-isystem` path. An installed compiler keeps them in `include/` next to
+/// its executable or next to the `bin/` directory it is in.
+fn system_include_path() -> PathBuf {
+    let executable = std::env::current_exe().and_then(|x| x.canonicalize()).ok();
+    executable
+        .iter()
+        .flat_map(|x| x.parent())
+        .flat_map(|x| [x.join("include"), x.join("../include")])
+        .find(|x| x.join("stdio.h").is_file())
+        .unwrap_or_else(|| PathBuf::from(SOURCE_INCLUDE_PATH))
+}
 
 pub struct Preprocessor<'a> {
-    lexer: Lexer<'a>,
-    input: &'a str,
-    macros: Vec<Macro>,
-    expanded: bool,
+    bump: &'a Bump,
+    include_paths: Vec<PathBuf>,
+    macros: Vec<Macro<'a>>,
+    conditions: Vec<Condition<'a>>,
+    // the files that are currently being expanded, the innermost last. The
+    // name is the path the file was found under.
+    files: Vec<(PathBuf, &'a str)>,
+    // what `#line` set for each of `files`: the difference between the line
+    // numbers that are reported and the real ones, and the file name
+    presumed: Vec<(isize, &'a str)>,
+    // files that contained `#pragma once`
+    once: Vec<PathBuf>,
+    // files that are completely wrapped in `#ifndef NAME ... #endif`
+    guards: Vec<(PathBuf, &'a str)>,
+    // the next value of `__COUNTER__`
+    counter: Cell<usize>,
 }
 
+/// one level of `#if`/`#ifdef`/`#ifndef` nesting
 #[derive(Debug)]
-pub enum Macro {
-    Literal {
-        name: String,
-        replacement: String,
-    },
-    Function {
-        name: String,
-        parameter: Vec<String>,
-        replacement: String,
-    },
+struct Condition<'a> {
+    location: SrcLocation<'a>,
+    // the current branch is emitted
+    active: bool,
+    // one of the branches was (or cannot be) emitted, the rest is skipped
+    taken: bool,
+    has_else: bool,
 }
 
-impl Macro {
-    pub fn name(&self) -> &String {
-        match self {
-            Macro::Literal {
-                name,
-                replacement: _,
-            } => name,
-            Macro::Function {
-                name,
-                parameter: _,
-                replacement: _,
-            } => name,
-        }
-    }
-}
-
-impl Preprocessor<'_> {
-    pub fn new<'a>(content: &'a str) -> Preprocessor<'a> {
+impl<'a> Preprocessor<'a> {
+    pub fn new(bump: &'a Bump, mut include_paths: Vec<PathBuf>) -> Preprocessor<'a> {
+        include_paths.push(system_include_path());
         Preprocessor {
-            lexer: Lexer::new(content),
-            input: content,
+            bump,
+            include_paths,
             macros: Vec::new(),
-            expanded: false,
+            conditions: Vec::new(),
+            files: Vec::new(),
+            presumed: Vec::new(),
+            once: Vec::new(),
+            guards: Vec::new(),
+            counter: Cell::new(0),
         }
     }
 
-    pub fn expand(&mut self) -> Result<String, > {
-        let mut output = String::new();
-
-        loop {
-            let token = self.lexer.next();
+    pub fn expand(&mut self, file_name: &'a str, content: &'a str) -> Result<&'a str, Error<'a>> {
+        let path = Path::new(file_name);
+        let path = path.canonicalize().unwrap_or(path.to_path_buf());
+        self.files.push((path, file_name));
+        self.presumed.push((0, file_name));
+        let result = self.expand_file(content);
+        self.files.pop();
+        self.presumed.pop();
+        result
+    }
 
-            if token == Token::HASHTAG {
-                self.parse_macro(&mut output)?;
+    fn expand_file(&mut self, content: &'a str) -> Result<&'a str, Error<'a>> {
+        let (_, name) = self.files.last().unwrap().clone();
+        let tokens = Lexer::preprocessing_tokens(content, name);
+        if let Some(guard) = Self::include_guard(content, &tokens) {
+            let (file, _) = self.files.last().unwrap().clone();
+            if !self.guards.iter().any(|(x, _)| *x == file) {
+                self.guards.push((file, guard));
+            }
+        }
+        // the line marker tells the lexer where the following tokens come from
+        let mut output = format!("# 1 \"{}\"\n", name);
+        let mut line = 1;
+        let mut column = 1;
+        let mut index = 0;
+        let conditions = self.conditions.len();
+
+        while tokens[index].0 != TokenKind::EOF {
+            if tokens[index].0 == TokenKind::HASHTAG && Self::starts_line(content, &tokens, index) {
+                let end = Self::line_end(content, &tokens, index);
+                let directive: Vec<Token<'a>> = tokens[index + 1..end]
+                    .iter()
+                    .filter(|(kind, _)| *kind != TokenKind::ESCAPELINE)
+                    .copied()
+                    .collect();
+                if !self.parse_condition(tokens[index].1, &directive, conditions)?
+                    && self.is_active()
+                {
+                    let length = output.len();
+                    let presumed = self.presumed.last().copied();
+                    self.parse_macro(tokens[index].1, &directive, &mut output)?;
+                    // an included file was inserted or `#line` moved the
+                    // position, so it has to be restated before the next token
+                    if output.len() != length || self.presumed.last().copied() != presumed {
+                        line = 0;
+                    }
+                }
+                index = end;
                 continue;
             }
 
-            if token == Token::EOF {
-                break;
+            // everything up to the next directive is expanded in one go, so
+            // that macro calls may span multiple lines
+            let mut end = index;
+            while tokens[end].0 != TokenKind::EOF
+                && !(tokens[end].0 == TokenKind::HASHTAG
+                    && Self::starts_line(content, &tokens, end))
+            {
+                end += 1;
             }
-
-            self.expand_macros(&mut output)?;
+            if !self.is_active() {
+                index = end;
+                continue;
+            }
+            let expanded = self.expand_macros(&tokens[index..end])?;
+            let mut last_in_place = false;
+            for (_, location) in expanded {
+                if line == 0 {
+                    // a line marker has to start a line of its own
+                    if !output.ends_with('\n') {
+                        output.push('\n');
+                    }
+                    let (offset, name) = self.presumed.last().unwrap();
+                    let presumed = location.line as isize + offset;
+                    output.push_str(&format!("# {} \"{}\"\n", presumed, name));
+                    line = location.line;
+                    column = 1;
+                }
+                // keep the lines and columns of the source file intact
+                while line < location.line {
+                    output.push('\n');
+                    line += 1;
+                    column = 1;
+                }
+                // tokens that were adjacent in the source stay adjacent. Anything
+                // that came out of a macro is separated, so it cannot be glued
+                // to its neighbours by the lexer.
+                let in_place = Self::in_place(&tokens, &location);
+                if column < location.column {
+                    output.push_str(&" ".repeat(location.column - column));
+                    column = location.column;
+                } else if column > 1 && !(column == location.column && last_in_place && in_place) {
+                    output.push(' ');
+                    column += 1;
+                }
+                output.push_str(location.src);
+                column += location.src.len();
+                last_in_place = in_place;
+            }
+            index = end;
         }
 
-        // expand recursivly until we are finished!
-        if self.expanded {
-            let mut child = Preprocessor::new(&mut output);
-            return child.expand();
+        // every conditional has to be closed in the file that opened it
+        if let Some(condition) = self.conditions.get(conditions) {
+            return Err(Error::UnterminatedConditional {
+                location: condition.location,
+            });
         }
 
-        //TODO: remove the defintions
-        let mut lexer = Lexer::new(&output);
-        let mut output = String::new();
-        while lexer.peek() != Token::EOF {
-            while lexer.peek() == Token::HASHTAG {
-                lexer.consume_line();
+        Ok(self.bump.alloc_str(&output))
+    }
+
+    /// finds the macro of an include guard, if the whole file is inside of
+    /// a single `#ifndef NAME` block
+    fn include_guard(content: &str, tokens: &[Token<'a>]) -> Option<&'a str> {
+        let guard = match tokens {
+            [(TokenKind::HASHTAG, _), (TokenKind::IDENT, directive), (TokenKind::IDENT, name), ..]
+                if directive.src == "ifndef" =>
+            {
+                name.src
+            }
+            _ => return None,
+        };
+        let mut depth = 0;
+        let mut index = 0;
+        while tokens[index].0 != TokenKind::EOF {
+            let end = Self::line_end(content, tokens, index);
+            if tokens[index].0 == TokenKind::HASHTAG && Self::starts_line(content, tokens, index) {
+                match Self::directive_name(tokens.get(index + 1)) {
+                    Some("if" | "ifdef" | "ifndef") => depth += 1,
+                    Some("elif" | "else") if depth == 1 => return None,
+                    Some("endif") => {
+                        depth -= 1;
+                        if depth == 0 {
+                            return (tokens[end].0 == TokenKind::EOF).then_some(guard);
+                        }
+                    }
+                    _ => (),
+                }
+            } else if depth == 0 {
+                return None;
             }
-            lexer.next();
-            output += lexer.last_string();
-            output += " ";
+            index = end;
         }
-
-        output = output.replace("\\\n", " ");
-
-        Ok(output)
+        None
     }
 
-    fn parse_define(&mut self, output: &mut String) -> Result<Macro, > {
-        output.push_str(&"\n#");
-        output.push_str(self.lexer.expect(Token::DEFINE)?);
-        output.push(' ');
+    /// the name of the directive after a `#`. Only `if` and `else` are
+    /// keywords, the other directives are lexed as identifiers so that C
+    /// code can use their names.
+    fn directive_name(token: Option<&Token<'a>>) -> Option<&'a str> {
+        match token? {
+            (TokenKind::IF, _) => Some("if"),
+            (TokenKind::ELSE, _) => Some("else"),
+            (TokenKind::IDENT, location) => Some(location.src),
+            _ => None,
+        }
+    }
 
-        let name = self.lexer.expect(Token::IDENT)?.to_string();
+    fn is_active(&self) -> bool {
+        self.conditions.iter().all(|x| x.active)
+    }
 
-        output.push_str(&name);
-        let mut parameter = None;
+    /// handles the conditional directives, which have to be looked at even
+    /// inside of skipped blocks. Returns false for every other directive.
+    /// `first` is the nesting depth at which the current file started.
+    fn parse_condition(
+        &mut self,
+        hashtag: SrcLocation<'a>,
+        tokens: &[Token<'a>],
+        first: usize,
+    ) -> Result<bool, Error<'a>> {
+        let directive = match Self::directive_name(tokens.first()) {
+            Some(x) => x,
+            None => return Ok(false),
+        };
+        let location = tokens[0].1;
+        match directive {
+            "if" | "ifdef" | "ifndef" => {
+                // conditions inside of skipped blocks are not evaluated at all
+                let active = self.is_active()
+                    && match directive {
+                        "if" => self.evaluate_condition(location, &tokens[1..])?,
+                        "ifdef" => self.is_defined(location, &tokens[1..])?,
+                        _ => !self.is_defined(location, &tokens[1..])?,
+                    };
+                let taken = active || !self.is_active();
+                self.conditions.push(Condition {
+                    location: hashtag,
+                    active,
+                    taken,
+                    has_else: false,
+                });
+            }
+            "elif" | "else" => {
+                if self.conditions.len() <= first || self.conditions.last().unwrap().has_else {
+                    return Err(Error::UnmatchedDirective { location: hashtag });
+                }
+                let condition = self.conditions.pop().unwrap();
+                let active = !condition.taken
+                    && match directive {
+                        "elif" => self.evaluate_condition(location, &tokens[1..])?,
+                        _ => true,
+                    };
+                self.conditions.push(Condition {
+                    location: condition.location,
+                    active,
+                    taken: condition.taken || active,
+                    has_else: directive == "else",
+                });
+            }
+            "endif" => {
+                if self.conditions.len() <= first {
+                    return Err(Error::UnmatchedDirective { location: hashtag });
+                }
+                self.conditions.pop();
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
 
-        if self.lexer.peek() == Token::LPAREN {
-            self.lexer.next();
-            output.push('(');
-            let mut parameter_list = Vec::new();
-            loop {
-                let name = self.lexer.expect(Token::IDENT)?.to_string();
-                output.push_str(&name);
-                parameter_list.push(name);
+    fn is_defined(
+        &self,
+        location: SrcLocation<'a>,
+        tokens: &[Token<'a>],
+    ) -> Result<bool, Error<'a>> {
+        match tokens {
+            [(TokenKind::IDENT, name)] => Ok(self.macros.iter().any(|x| x.name() == name.src)),
+            _ => Err(Error::InvalidDirective { location }),
+        }
+    }
 
-                if self.lexer.peek() == Token::RPAREN {
-                    break;
+    fn evaluate_condition(
+        &self,
+        location: SrcLocation<'a>,
+        tokens: &[Token<'a>],
+    ) -> Result<bool, Error<'a>> {
+        // `defined X` and `defined(X)` have to be replaced before any macro
+        // is expanded
+        let mut replaced = Vec::new();
+        let mut index = 0;
+        while index < tokens.len() {
+            let (kind, defined) = tokens[index];
+            index += 1;
+            if kind != TokenKind::IDENT || defined.src != "defined" {
+                replaced.push(tokens[index - 1]);
+                continue;
+            }
+            let name = match &tokens[index..] {
+                [(TokenKind::IDENT, name), ..] => {
+                    index += 1;
+                    name
+                }
+                [(TokenKind::LPAREN, _), (TokenKind::IDENT, name), (TokenKind::RPAREN, _), ..] => {
+                    index += 3;
+                    name
                 }
+                _ => return Err(Error::InvalidDirective { location: defined }),
+            };
+            let value = if self.macros.iter().any(|x| x.name() == name.src) {
+                "1"
+            } else {
+                "0"
+            };
+            replaced.push((
+                TokenKind::INTLITERAL,
+                SrcLocation {
+                    src: value,
+                    ..defined
+                },
+            ));
+        }
 
-                self.lexer.expect(Token::COMMA)?;
-                output.push(',');
+        let mut expanded = self.expand_macros(&replaced)?;
+        if expanded.is_empty() {
+            return Err(Error::InvalidConstantExpression { location });
+        }
+        // every integer is as wide as a long here, so a constant without `U`
+        // is only unsigned if it does not fit into one
+        for (kind, literal) in expanded.iter_mut() {
+            if *kind == TokenKind::INTLITERAL && !literal.src.contains(['u', 'U', 'l', 'L']) {
+                literal.src = self.bump.alloc_str(&format!("{}L", literal.src));
             }
-            self.lexer.next();
-            output.push(')');
-            parameter = Some(parameter_list);
         }
+        let last = expanded.last().unwrap().1;
+        expanded.push((TokenKind::EOF, SrcLocation { src: "", ..last }));
+
+        let tokens = self.bump.alloc(expanded);
+        let mut parser = Parser::new(tokens, self.bump);
+        let expression = parser.expression()?;
+        if parser.peek() != TokenKind::EOF {
+            return Err(Error::InvalidConstantExpression {
+                location: parser.current().1,
+            });
+        }
+        Ok(Self::evaluate(expression, location)? != 0)
+    }
 
-        let start_index = self.lexer.current_index();
-        self.lexer.consume_line();
-        let end_index = self.lexer.current_index();
-
-        let replacement = self.input[start_index..end_index].to_string();
-        output.push_str(&replacement);
-        output.push('\n');
-
-        Ok(match parameter {
-            Some(parameter) => Macro::Function {
-                name: name,
-                parameter: parameter,
-                replacement: replacement,
-            },
-            None => Macro::Literal {
-                name: name,
-                replacement: replacement,
+    /// true if the value of the expression is unsigned, then it converts
+    /// the signed side of an operation to unsigned
+    fn is_unsigned(expression: &Expression<'a>) -> bool {
+        match expression {
+            Expression::UIntLiteral(_) | Expression::ULongLiteral(_) => true,
+            Expression::Unary {
+                expression,
+                operation: UnaryOps::NEG | UnaryOps::COMPLEMENT,
+            } => Self::is_unsigned(expression),
+            Expression::BinaryExpression {
+                lhs,
+                rhs,
+                operation,
+            } => match operation {
+                BinaryOps::AND
+                | BinaryOps::OR
+                | BinaryOps::EQ
+                | BinaryOps::NE
+                | BinaryOps::LT
+                | BinaryOps::GT
+                | BinaryOps::LE
+                | BinaryOps::GE => false,
+                BinaryOps::SHL | BinaryOps::SHR => Self::is_unsigned(lhs),
+                BinaryOps::COMMA => Self::is_unsigned(rhs),
+                _ => Self::is_unsigned(lhs) || Self::is_unsigned(rhs),
             },
-        })
+            Expression::Ternary {
+                then, otherwise, ..
+            } => Self::is_unsigned(then) || Self::is_unsigned(otherwise),
+            _ => false,
+        }
     }
 
-    fn parse_macro(&mut self, output: &mut String) -> Result<(), > {
-        match self.lexer.peek() {
-            Token::DEFINE => {
-                let parsed_macro = self.parse_define(output)?;
-                self.macros.push(parsed_macro);
-                return Ok(());
-            }
-            Token::INCLUDE => {
-                self.expanded = true;
-                self.lexer.expect(Token::INCLUDE)?;
-                let name = self.lexer.expect(Token::STRINGLIT)?;
-                let file_name = name[1..name.len() - 1].to_string();
-
-                let file = std::fs::read_to_string(&file_name);
-                return match file {
-                    Ok(x) => {
-                        output.push_str(&x);
-                        return Ok(());
-                    }
-                    Err(_) => self.lexer.error(format!("Cannot open file: {file_name}")),
+    /// evaluates the controlling expression of `#if`. Identifiers that are
+    /// left after the macro expansion count as 0.
+    fn evaluate(expression: &Expression<'a>, location: SrcLocation<'a>) -> Result<i64, Error<'a>> {
+        Ok(match expression {
+            Expression::IntLiteral(value) => *value as i64,
+            Expression::UIntLiteral(value) => *value as i64,
+            Expression::LongLiteral(value) => *value,
+            Expression::ULongLiteral(value) => *value as i64,
+            Expression::CharLiteral(value) => *value as i64,
+            Expression::NamedVariable { .. } => 0,
+            Expression::Unary {
+                expression,
+                operation,
+            } => {
+                let value = Self::evaluate(expression, location)?;
+                match operation.evaluate(value) {
+                    Some(value) => value,
+                    None => return Err(Error::InvalidConstantExpression { location }),
+                }
+            }
+            Expression::BinaryExpression {
+                lhs,
+                rhs,
+                operation,
+            } => {
+                // shifts keep the type of the left side
+                let unsigned = match operation {
+                    BinaryOps::SHL | BinaryOps::SHR => Self::is_unsigned(lhs),
+                    _ => Self::is_unsigned(lhs) || Self::is_unsigned(rhs),
                 };
+                let lhs = Self::evaluate(lhs, location)?;
+                // the right side of && and || is only evaluated if needed
+                match operation {
+                    BinaryOps::AND if lhs == 0 => return Ok(0),
+                    BinaryOps::OR if lhs != 0 => return Ok(1),
+                    _ => (),
+                }
+                let rhs = Self::evaluate(rhs, location)?;
+                let value = if unsigned {
+                    operation.evaluate_unsigned(lhs, rhs)
+                } else {
+                    operation.evaluate(lhs, rhs)
+                };
+                match value {
+                    Some(value) => value,
+                    None => return Err(Error::InvalidConstantExpression { location }),
+                }
             }
-            x => panic!("Can't parse macro! Found token: {:?}", x),
-        }
-    }
-
-    fn expand_macros(&mut self, output: &mut String) -> Result<bool, > {
-        let name = self.lexer.last_string();
-        if let Some(m) = self.macros.iter().find(|x| x.name() == name) {
-            self.expanded = true;
-            match m {
-                Macro::Literal {
-                    name: _,
-                    replacement,
-                } => output.push_str(&replacement),
-                Macro::Function {
-                    name: _,
-                    parameter,
-                    replacement,
-                } => {
-                    self.lexer.expect(Token::LPAREN)?;
-
-                    let mut arguments = Vec::new();
-                    loop {
-                        let mut parathese = 0;
-                        let start_index = self.lexer.current_index();
-
-                        loop {
-                            if self.lexer.peek() == Token::LPAREN {
-                                parathese += 1;
-                            }
-                            if (self.lexer.peek() == Token::RPAREN
-                                || self.lexer.peek() == Token::COMMA)
-                                && parathese == 0
-                            {
-                                break;
-                            }
-                            if self.lexer.peek() == Token::RPAREN {
-                                parathese -= 1;
-                            }
-                            if self.lexer.peek() == Token::EOF {
-                                panic!("Cannot parse MACRO argument!");
-                            }
-                            self.lexer.next();
-                        }
+            Expression::Ternary {
+                condition,
+                then,
+                otherwise,
+            } => {
+                if Self::evaluate(condition, location)? != 0 {
+                    Self::evaluate(then, location)?
+                } else {
+                    Self::evaluate(otherwise, location)?
+                }
+            }
+            _ => return Err(Error::InvalidConstantExpression { location }),
+        })
+    }
 
-                        let end_index = self.lexer.current_index();
-                        let argument = self.input[start_index..end_index].to_string();
-                        arguments.push(argument);
+    /// true if the token is the first of a logical line. `tokens` were lexed
+    /// from `content`. A comment is a space, so a newline inside of one does
+    /// not end the line.
+    fn starts_line(content: &str, tokens: &[Token<'a>], index: usize) -> bool {
+        if index == 0 {
+            return true;
+        }
+        let (previous, next) = (tokens[index - 1], tokens[index]);
+        if previous.1.line == next.1.line || previous.0 == TokenKind::ESCAPELINE {
+            return false;
+        }
+        let offset = |src: &str| src.as_ptr() as usize - content.as_ptr() as usize;
+        let start = offset(previous.1.src) + previous.1.src.len();
+        Lexer::ends_line(&content[start..offset(next.1.src)])
+    }
 
-                        if self.lexer.peek() == Token::RPAREN {
-                            break;
-                        }
-                        self.lexer.expect(Token::COMMA)?;
+    /// true if the token was not moved by a macro expansion
+    fn in_place(tokens: &[Token<'a>], location: &SrcLocation<'a>) -> bool {
+        let position = tokens.binary_search_by_key(&(location.src.as_ptr() as usize), |x| {
+            x.1.src.as_ptr() as usize
+        });
+        match position {
+            Ok(index) => {
+                tokens[index].1.line == location.line && tokens[index].1.column == location.column
+            }
+            Err(_) => false,
+        }
+    }
+
+    fn line_end(content: &str, tokens: &[Token<'a>], index: usize) -> usize {
+        let mut end = index + 1;
+        while tokens[end].0 != TokenKind::EOF && !Self::starts_line(content, tokens, end) {
+            end += 1;
+        }
+        end
+    }
+
+    fn parse_macro(
+        &mut self,
+        hashtag: SrcLocation<'a>,
+        tokens: &[Token<'a>],
+        output: &mut String,
+    ) -> Result<(), Error<'a>> {
+        match tokens.first() {
+            // the null directive does nothing
+            None => Ok(()),
+            Some((TokenKind::IDENT, location)) if location.src == "define" => {
+                let parsed_macro = self.parse_define(tokens)?;
+                self.macros.retain(|x| x.name() != parsed_macro.name());
+                self.macros.push(parsed_macro);
+                Ok(())
+            }
+            Some((TokenKind::IDENT, location)) if location.src == "undef" => match tokens.get(1) {
+                Some((TokenKind::IDENT, name)) if tokens.len() == 2 => {
+                    self.macros.retain(|x| x.name() != name.src);
+                    Ok(())
+                }
+                _ => Err(Error::InvalidDirective {
+                    location: *location,
+                }),
+            },
+            Some((TokenKind::IDENT, location)) if location.src == "include" => {
+                let (file_name, quoted, location) = self.parse_include(*location, &tokens[1..])?;
+                let found = match self.find_include(file_name, quoted) {
+                    Some(x) => x,
+                    None => {
+                        return Err(Error::CannotOpenFile {
+                            name: file_name,
+                            location,
+                        })
                     }
+                };
+                let name = self.bump.alloc_str(&found.display().to_string());
+                let path = found.canonicalize().unwrap_or(found);
+                let guarded = self.guards.iter().any(|(file, guard)| {
+                    *file == path && self.macros.iter().any(|x| x.name() == *guard)
+                });
+                if guarded || self.once.contains(&path) {
+                    return Ok(());
+                }
+                // a file that includes itself would be expanded forever
+                if self.files.iter().any(|(file, _)| *file == path) {
+                    return Err(Error::RecursiveInclude {
+                        name: file_name,
+                        location,
+                    });
+                }
 
-                    self.lexer.expect(Token::RPAREN)?;
-
-                    let mut replacement_lexer = Lexer::new(&replacement);
-
-                    loop {
-                        match replacement_lexer.next() {
-                            Token::IDENT => {
-                                let argument_name = replacement_lexer.last_string();
-                                let index = parameter
-                                    .iter()
-                                    .enumerate()
-                                    .find(|(_, name)| argument_name == *name);
-                                match index {
-                                    Some((index, _)) => output.push_str(&arguments[index]),
-                                    None => output.push_str(replacement_lexer.last_string()),
-                                }
-                            }
-                            Token::EOF => {
-                                break;
-                            }
-                            _ => output.push_str(replacement_lexer.last_string()),
-                        }
+                let content = match std::fs::read_to_string(&path) {
+                    Ok(x) => self.bump.alloc_str(&x),
+                    Err(_) => {
+                        return Err(Error::CannotOpenFile {
+                            name: file_name,
+                            location,
+                        })
                     }
+                };
+                self.files.push((path, name));
+                self.presumed.push((0, name));
+                let expanded = self.expand_file(content);
+                self.files.pop();
+                self.presumed.pop();
+                output.push_str(expanded?);
+                output.push('\n');
+                Ok(())
+            }
+            Some((TokenKind::IDENT, location)) if location.src == "line" => {
+                let expanded = self.expand_macros(&tokens[1..])?;
+                let (number, name) = match expanded.as_slice() {
+                    [(TokenKind::INTLITERAL, number)] => (number, None),
+                    [(TokenKind::INTLITERAL, number), (TokenKind::STRINGLIT, name)] => {
+                        (number, Some(&name.src[1..name.src.len() - 1]))
+                    }
+                    _ => {
+                        return Err(Error::InvalidDirective {
+                            location: *location,
+                        })
+                    }
+                };
+                let number: isize = match number.src.parse() {
+                    Ok(x) if x > 0 => x,
+                    _ => return Err(Error::InvalidDirective { location: *number }),
+                };
+                // the number is the one of the next line
+                let next = tokens.last().unwrap().1.line as isize + 1;
+                let presumed = self.presumed.last_mut().unwrap();
+                *presumed = (number - next, name.unwrap_or(presumed.1));
+                Ok(())
+            }
+            Some((TokenKind::IDENT, location)) if location.src == "pragma" => {
+                if let Some((TokenKind::IDENT, argument)) = tokens.get(1) {
+                    if argument.src == "once" {
+                        let (file, _) = self.files.last().unwrap().clone();
+                        self.once.push(file);
+                    }
+                }
+                // unknown pragmas are ignored
+                Ok(())
+            }
+            Some(_) => Err(Error::InvalidDirective { location: hashtag }),
+        }
+    }
+
+    /// reads the file name of `#include "name"` or `#include <name>`. The
+    /// returned flag is set for the quoted form.
+    fn parse_include(
+        &self,
+        location: SrcLocation<'a>,
+        tokens: &[Token<'a>],
+    ) -> Result<(&'a str, bool, SrcLocation<'a>), Error<'a>> {
+        match tokens {
+            [(TokenKind::STRINGLIT, name)] => Ok((&name.src[1..name.src.len() - 1], true, *name)),
+            [(TokenKind::LT, start), rest @ .., (TokenKind::GT, _)] => {
+                // the lexer does not know header names, so they are glued
+                // back together from their pieces
+                let name: String = rest.iter().map(|(_, x)| x.src).collect();
+                Ok((self.bump.alloc_str(&name), false, *start))
+            }
+            [(TokenKind::IDENT, _), ..] => {
+                let expanded = self.expand_macros(tokens)?;
+                match expanded.first() {
+                    Some((TokenKind::IDENT, _)) | None => Err(Error::InvalidDirective { location }),
+                    Some(_) => self.parse_include(location, &expanded),
                 }
             }
-            return Ok(true);
+            _ => Err(Error::InvalidDirective { location }),
         }
+    }
 
-        output.push_str(" ");
-        output.push_str(self.lexer.last_string());
-        return Ok(false);
+    /// quoted includes are searched next to the including file first, then
+    /// both forms go through the include paths
+    fn find_include(&self, file_name: &str, quoted: bool) -> Option<PathBuf> {
+        let mut candidates = Vec::new();
+        if quoted {
+            // relative to the name the file was opened under, so that nested
+            // headers are reported with relative names as well
+            if let Some(directory) = self.files.last().and_then(|(_, x)| Path::new(x).parent()) {
+                candidates.push(directory.join(file_name));
+            }
+        }
+        for directory in &self.include_paths {
+            candidates.push(directory.join(file_name));
+        }
+        candidates.into_iter().find(|x| x.is_file())
     }
 }
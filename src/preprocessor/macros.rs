@@ -0,0 +1,507 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{
+    error::Error,
+    lexer::{tokens::TokenKind, Lexer, SrcLocation, Token},
+};
+
+use super::Preprocessor;
+
+/// the names of the macros a token came from. A token is never expanded by
+/// a macro in its own hide set, which stops self-referential macros.
+pub type HideSet<'a> = &'a [&'a str];
+
+type Hidden<'a> = (Token<'a>, HideSet<'a>);
+
+#[derive(Debug)]
+pub enum Macro<'a> {
+    Literal {
+        name: &'a str,
+        replacement: Vec<Token<'a>>,
+    },
+    Function {
+        name: &'a str,
+        parameter: Vec<&'a str>,
+        // the last parameter is `__VA_ARGS__` and takes the remaining arguments
+        variadic: bool,
+        replacement: Vec<Token<'a>>,
+    },
+}
+
+impl<'a> Macro<'a> {
+    pub fn name(&self) -> &'a str {
+        match self {
+            Macro::Literal { name, .. } => name,
+            Macro::Function { name, .. } => name,
+        }
+    }
+}
+
+static MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// true if there is no whitespace between the two tokens
+fn adjacent(lhs: &SrcLocation, rhs: &SrcLocation) -> bool {
+    lhs.line == rhs.line && rhs.column == lhs.column + lhs.src.len()
+}
+
+/// `##` is lexed as two `#` tokens
+fn is_paste(tokens: &[Token], index: usize) -> bool {
+    match (tokens.get(index), tokens.get(index + 1)) {
+        (Some((TokenKind::HASHTAG, first)), Some((TokenKind::HASHTAG, second))) => {
+            adjacent(first, second)
+        }
+        _ => false,
+    }
+}
+
+impl<'a> Preprocessor<'a> {
+    pub(super) fn parse_define(&mut self, tokens: &[Token<'a>]) -> Result<Macro<'a>, Error<'a>> {
+        let (name, name_location) = match tokens.get(1) {
+            Some((TokenKind::IDENT, location)) => (location.src, *location),
+            Some((_, location)) => {
+                return Err(Error::InvalidDirective {
+                    location: *location,
+                })
+            }
+            None => {
+                return Err(Error::InvalidDirective {
+                    location: tokens[0].1,
+                })
+            }
+        };
+
+        // only a parenthesis directly after the name starts a parameter list
+        let is_function = match tokens.get(2) {
+            Some((TokenKind::LPAREN, location)) => adjacent(&name_location, location),
+            _ => false,
+        };
+
+        if !is_function {
+            let replacement = tokens[2..].to_vec();
+            Self::check_paste(&replacement, name_location)?;
+            return Ok(Macro::Literal { name, replacement });
+        }
+
+        let mut parameter = Vec::new();
+        let mut variadic = false;
+        let mut index = 3;
+        loop {
+            match &tokens[index.min(tokens.len())..] {
+                [(TokenKind::RPAREN, _), ..] if parameter.is_empty() => break,
+                [(TokenKind::IDENT, location), ..] => parameter.push(location.src),
+                [(TokenKind::DOT, _), (TokenKind::DOT, _), (TokenKind::DOT, _), ..] => {
+                    parameter.push("__VA_ARGS__");
+                    variadic = true;
+                    index += 2;
+                }
+                [(_, location), ..] => {
+                    return Err(Error::InvalidDirective {
+                        location: *location,
+                    })
+                }
+                [] => {
+                    return Err(Error::InvalidDirective {
+                        location: name_location,
+                    })
+                }
+            }
+            index += 1;
+            match tokens.get(index) {
+                Some((TokenKind::RPAREN, _)) => break,
+                Some((TokenKind::COMMA, _)) if !variadic => index += 1,
+                Some((_, location)) => {
+                    return Err(Error::InvalidDirective {
+                        location: *location,
+                    })
+                }
+                None => {
+                    return Err(Error::InvalidDirective {
+                        location: name_location,
+                    })
+                }
+            }
+        }
+
+        let replacement = tokens[index + 1..].to_vec();
+        Self::check_paste(&replacement, name_location)?;
+
+        Ok(Macro::Function {
+            name,
+            parameter,
+            variadic,
+            replacement,
+        })
+    }
+
+    /// `##` needs an operand on both sides
+    fn check_paste(replacement: &[Token<'a>], location: SrcLocation<'a>) -> Result<(), Error<'a>> {
+        if is_paste(replacement, 0) || is_paste(replacement, replacement.len().max(2) - 2) {
+            return Err(Error::InvalidTokenPaste { location });
+        }
+        Ok(())
+    }
+
+    /// replaces every macro in `tokens`. The replacement is put back in front
+    /// of the remaining input, so it is scanned again for further macros.
+    pub(super) fn expand_macros(&self, tokens: &[Token<'a>]) -> Result<Vec<Token<'a>>, Error<'a>> {
+        let tokens = tokens
+            .iter()
+            .filter(|(kind, _)| *kind != TokenKind::ESCAPELINE)
+            .map(|x| (*x, &[] as HideSet))
+            .collect();
+        Ok(self
+            .expand_hidden(tokens)?
+            .into_iter()
+            .map(|(token, _)| token)
+            .collect())
+    }
+
+    fn expand_hidden(&self, tokens: Vec<Hidden<'a>>) -> Result<Vec<Hidden<'a>>, Error<'a>> {
+        let mut output = Vec::new();
+        let mut pending: Vec<Hidden<'a>> = tokens.into_iter().rev().collect();
+
+        while let Some(hidden) = pending.pop() {
+            let ((kind, location), hide_set) = hidden;
+            if kind != TokenKind::IDENT || hide_set.contains(&location.src) {
+                output.push(hidden);
+                continue;
+            }
+            if let Some(token) = self.builtin_macro(location) {
+                output.push((token, hide_set));
+                continue;
+            }
+            match self.macros.iter().find(|x| x.name() == location.src) {
+                None => output.push(hidden),
+                Some(Macro::Literal { name, replacement }) => {
+                    let hide_set = self.hide(hide_set, name);
+                    let replacement: Vec<_> = self
+                        .substitute(replacement, &[], &[], false)?
+                        .into_iter()
+                        .map(|(token, _)| (token, hide_set))
+                        .collect();
+                    Self::push_replacement(&mut pending, &replacement, location);
+                }
+                Some(Macro::Function {
+                    name,
+                    parameter,
+                    variadic,
+                    replacement,
+                }) => {
+                    if pending.last().map(|x| x.0 .0) != Some(TokenKind::LPAREN) {
+                        output.push(hidden);
+                        continue;
+                    }
+                    pending.pop();
+                    let (mut arguments, closing) = Self::parse_arguments(
+                        &mut pending,
+                        name,
+                        parameter.len(),
+                        *variadic,
+                        location,
+                    )?;
+                    // `F()` passes a single empty argument
+                    if parameter.is_empty() && arguments.len() == 1 && arguments[0].is_empty() {
+                        arguments.clear();
+                    }
+                    // the variable arguments may be left out completely
+                    if *variadic && arguments.len() + 1 == parameter.len() {
+                        arguments.push(Vec::new());
+                    }
+                    if arguments.len() != parameter.len() {
+                        return Err(Error::MacroArgumentCountMismatch {
+                            name,
+                            expected: parameter.len(),
+                            found: arguments.len(),
+                            location,
+                        });
+                    }
+
+                    // only names hidden at both the name and the closing
+                    // parenthesis stay hidden
+                    let kept: Vec<&'a str> = hide_set
+                        .iter()
+                        .filter(|x| closing.contains(x))
+                        .copied()
+                        .collect();
+                    let hide_set = self.hide(&kept, name);
+                    let substituted = self.substitute(replacement, parameter, &arguments, true)?;
+                    let substituted: Vec<_> = substituted
+                        .into_iter()
+                        .map(|(token, hidden)| (token, self.union(hidden, hide_set)))
+                        .collect();
+                    Self::push_replacement(&mut pending, &substituted, location);
+                }
+            }
+        }
+        Ok(output)
+    }
+
+    /// puts the arguments into the replacement list and applies `#` and `##`.
+    /// `#` is only an operator in function-like macros.
+    fn substitute(
+        &self,
+        replacement: &[Token<'a>],
+        parameter: &[&'a str],
+        arguments: &[Vec<Hidden<'a>>],
+        function_like: bool,
+    ) -> Result<Vec<Hidden<'a>>, Error<'a>> {
+        let find_argument = |index: usize| -> Option<&Vec<Hidden<'a>>> {
+            match replacement.get(index) {
+                Some((TokenKind::IDENT, location)) => parameter
+                    .iter()
+                    .position(|x| *x == location.src)
+                    .map(|x| &arguments[x]),
+                _ => None,
+            }
+        };
+
+        let mut output: Vec<Hidden<'a>> = Vec::new();
+        let mut index = 0;
+        while index < replacement.len() {
+            let (kind, location) = replacement[index];
+
+            if is_paste(replacement, index) {
+                index += 2;
+                let rhs = match find_argument(index) {
+                    Some(x) => x.clone(),
+                    None => vec![(replacement[index], &[] as HideSet)],
+                };
+                index += 1;
+                let mut rhs = rhs.into_iter();
+                if let Some((first, _)) = rhs.next() {
+                    // pasting onto an empty argument leaves the right side alone
+                    match output.pop() {
+                        Some((last, _)) => output.push((self.paste(last, first)?, &[])),
+                        None => output.push((first, &[])),
+                    }
+                }
+                output.extend(rhs);
+                continue;
+            }
+
+            if kind == TokenKind::HASHTAG && function_like {
+                let argument = match find_argument(index + 1) {
+                    Some(x) => x,
+                    None => return Err(Error::InvalidDirective { location }),
+                };
+                output.push((self.stringify(location, argument), &[]));
+                index += 2;
+                continue;
+            }
+
+            // GNU extension: `, ## __VA_ARGS__` drops the comma if there are
+            // no variable arguments
+            if kind == TokenKind::COMMA
+                && is_paste(replacement, index + 1)
+                && parameter.last() == Some(&"__VA_ARGS__")
+                && matches!(replacement.get(index + 3), Some((_, x)) if x.src == "__VA_ARGS__")
+            {
+                let variable = arguments.last().unwrap();
+                if !variable.is_empty() {
+                    output.push((replacement[index], &[]));
+                    output.extend(self.expand_hidden(variable.clone())?);
+                }
+                index += 4;
+                continue;
+            }
+
+            match find_argument(index) {
+                // pasting an empty argument leaves the right side alone,
+                // which is not expanded either
+                Some(argument) if argument.is_empty() && is_paste(replacement, index + 1) => {
+                    index += 3;
+                    match find_argument(index) {
+                        Some(rhs) => output.extend(rhs.iter().copied()),
+                        None => output.push((replacement[index], &[])),
+                    }
+                    index += 1;
+                    continue;
+                }
+                // operands of `##` are not expanded
+                Some(argument) if is_paste(replacement, index + 1) => {
+                    output.extend(argument.iter().copied())
+                }
+                Some(argument) => output.extend(self.expand_hidden(argument.clone())?),
+                None => output.push((replacement[index], &[])),
+            }
+            index += 1;
+        }
+        Ok(output)
+    }
+
+    fn paste(&self, lhs: Token<'a>, rhs: Token<'a>) -> Result<Token<'a>, Error<'a>> {
+        let spelling = self.bump.alloc_str(&format!("{}{}", lhs.1.src, rhs.1.src));
+        match Lexer::tokenize(spelling, lhs.1.file).as_deref() {
+            Ok([(kind, location), (TokenKind::EOF, _)]) if location.src.len() == spelling.len() => {
+                Ok((
+                    *kind,
+                    SrcLocation {
+                        src: spelling,
+                        ..lhs.1
+                    },
+                ))
+            }
+            _ => Err(Error::InvalidTokenPaste { location: lhs.1 }),
+        }
+    }
+
+    /// `#parameter` turns the argument into a string literal
+    fn stringify(&self, location: SrcLocation<'a>, argument: &[Hidden<'a>]) -> Token<'a> {
+        let mut string = String::from("\"");
+        for (index, ((kind, token), _)) in argument.iter().enumerate() {
+            if index > 0 && !adjacent(&argument[index - 1].0 .1, token) {
+                string.push(' ');
+            }
+            match kind {
+                TokenKind::STRINGLIT | TokenKind::CHARLITERAL => {
+                    string.push_str(&token.src.replace('\\', "\\\\").replace('"', "\\\""))
+                }
+                _ => string.push_str(token.src),
+            }
+        }
+        string.push('"');
+        (
+            TokenKind::STRINGLIT,
+            SrcLocation {
+                src: self.bump.alloc_str(&string),
+                ..location
+            },
+        )
+    }
+
+    /// the predefined macros that change their value while preprocessing
+    fn builtin_macro(&self, location: SrcLocation<'a>) -> Option<Token<'a>> {
+        let (kind, spelling) = match location.src {
+            "__FILE__" => {
+                let (_, name) = self.presumed.last()?;
+                (TokenKind::STRINGLIT, format!("\"{}\"", name))
+            }
+            "__LINE__" => {
+                let (offset, _) = self.presumed.last()?;
+                let line = location.line as isize + offset;
+                (TokenKind::INTLITERAL, line.to_string())
+            }
+            "__COUNTER__" => {
+                let counter = self.counter.get();
+                self.counter.set(counter + 1);
+                (TokenKind::INTLITERAL, counter.to_string())
+            }
+            "__DATE__" | "__TIME__" => {
+                let seconds = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|x| x.as_secs())
+                    .unwrap_or(0);
+                let spelling = match location.src {
+                    "__DATE__" => {
+                        let (year, month, day) = civil_date(seconds / 86400);
+                        format!("\"{} {:>2} {}\"", MONTHS[month - 1], day, year)
+                    }
+                    _ => format!(
+                        "\"{:02}:{:02}:{:02}\"",
+                        seconds / 3600 % 24,
+                        seconds / 60 % 60,
+                        seconds % 60
+                    ),
+                };
+                (TokenKind::STRINGLIT, spelling)
+            }
+            _ => return None,
+        };
+        Some((
+            kind,
+            SrcLocation {
+                src: self.bump.alloc_str(&spelling),
+                ..location
+            },
+        ))
+    }
+
+    fn hide(&self, hide_set: &[&'a str], name: &'a str) -> HideSet<'a> {
+        let mut result = hide_set.to_vec();
+        result.push(name);
+        self.bump.alloc_slice_copy(&result)
+    }
+
+    fn union(&self, lhs: HideSet<'a>, rhs: HideSet<'a>) -> HideSet<'a> {
+        if lhs.is_empty() {
+            return rhs;
+        }
+        let mut result = lhs.to_vec();
+        result.extend(rhs.iter().filter(|x| !lhs.contains(x)));
+        self.bump.alloc_slice_copy(&result)
+    }
+
+    fn push_replacement(
+        pending: &mut Vec<Hidden<'a>>,
+        replacement: &[Hidden<'a>],
+        location: SrcLocation<'a>,
+    ) {
+        // the expanded tokens are reported at the place where the macro was used
+        for ((kind, replaced), hide_set) in replacement.iter().rev() {
+            pending.push((
+                (
+                    *kind,
+                    SrcLocation {
+                        src: replaced.src,
+                        ..location
+                    },
+                ),
+                hide_set,
+            ));
+        }
+    }
+
+    /// reads the arguments of a macro call up to the closing parenthesis.
+    /// For variadic macros the last argument takes all remaining commas. The
+    /// hide set of the closing parenthesis is returned as well.
+    fn parse_arguments(
+        pending: &mut Vec<Hidden<'a>>,
+        name: &'a str,
+        parameter_count: usize,
+        variadic: bool,
+        location: SrcLocation<'a>,
+    ) -> Result<(Vec<Vec<Hidden<'a>>>, HideSet<'a>), Error<'a>> {
+        let mut arguments = vec![Vec::new()];
+        let mut parentheses = 0;
+        loop {
+            let token = match pending.pop() {
+                Some(x) => x,
+                None => return Err(Error::UnterminatedMacroCall { name, location }),
+            };
+            match token.0 .0 {
+                TokenKind::RPAREN if parentheses == 0 => return Ok((arguments, token.1)),
+                TokenKind::COMMA
+                    if parentheses == 0 && !(variadic && arguments.len() == parameter_count) =>
+                {
+                    arguments.push(Vec::new());
+                    continue;
+                }
+                TokenKind::LPAREN => parentheses += 1,
+                TokenKind::RPAREN => parentheses -= 1,
+                _ => (),
+            }
+            arguments.last_mut().unwrap().push(token);
+        }
+    }
+}
+
+/// converts days since 1970-01-01 into (year, month, day)
+fn civil_date(days: u64) -> (u64, usize, u64) {
+    let days = days + 719468;
+    let era = days / 146097;
+    let day_of_era = days % 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + (month <= 2) as u64;
+    (year, month as usize, day)
+}
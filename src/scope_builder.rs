@@ -4,11 +4,12 @@ use bumpalo::Bump;
 
 use crate::{
     error::Error,
-    lexer::tokens::TokenKind,
+    lexer::{tokens::TokenKind, SrcLocation},
     parser::ast::{
-        ArrayExpression, Assignment, Compound, Decalrations, ElsePart, Expression, ForStatement,
-        Function, FunctionCall, IfStatement, Program, Statement, StructExpression, TypeDefinition,
-        TypeExpression, UnaryOps, WhileStatement,
+        ArrayExpression, AssignOperation, Assignment, BinaryOps, Compound, Decalrations,
+        DoWhileStatement, ElsePart,
+        Expression, ForStatement, Function, FunctionCall, IfStatement, Program, Statement,
+        StructExpression, SwitchStatement, TypeDefinition, TypeExpression, UnaryOps, WhileStatement,
     },
     scope_builder::ast::data_type::Struct,
     visitor::{Visitable, Visitor},
@@ -18,14 +19,17 @@ use self::ast::{
     resolved_array_expression::ResolvedArrayExpression,
     resolved_assignment::ResolvedAssignment,
     resolved_compound::ResolvedCompound,
+    resolved_do_while::ResolvedDoWhileStatement,
     resolved_expression::ResolvedExpression,
     resolved_for::ResolvedForStatement,
     resolved_function::ResolvedFunction,
     resolved_function_call::ResolvedFunctionCall,
+    resolved_global::{ResolvedGlobal, Section, StaticValue},
     resolved_if::{ResolvedElsePart, ResolvedIfStatement},
     resolved_program::ResolvedProgram,
     resolved_statement::ResolvedStatement,
     resolved_struct_expression::ResolvedStructExpression,
+    resolved_switch::ResolvedSwitchStatement,
     resolved_while::ResolvedWhileStatement,
     DataType, Variable,
 };
@@ -34,6 +38,8 @@ pub mod ast;
 pub struct Scope<'a> {
     types: Vec<HashMap<&'a str, DataType<'a>>>,
     variables: Vec<HashMap<&'a str, Variable<'a>>>,
+    // enumerators, they share the namespace of the variables
+    constants: Vec<HashMap<&'a str, i32>>,
     functions: Vec<HashMap<&'a str, &'a Function<'a>>>,
 }
 
@@ -42,6 +48,7 @@ impl<'a> Scope<'a> {
         Scope {
             types: vec![HashMap::new()],
             variables: vec![HashMap::new()],
+            constants: vec![HashMap::new()],
             functions: vec![HashMap::new()],
         }
     }
@@ -49,12 +56,14 @@ impl<'a> Scope<'a> {
     pub fn push(&mut self) {
         self.types.push(HashMap::new());
         self.variables.push(HashMap::new());
+        self.constants.push(HashMap::new());
         self.functions.push(HashMap::new());
     }
 
     pub fn pop(&mut self) {
         self.types.pop();
         self.variables.pop();
+        self.constants.pop();
         self.functions.push(HashMap::new());
     }
 
@@ -67,6 +76,11 @@ impl<'a> Scope<'a> {
         None
     }
 
+    /// true if `name` is already a type of the innermost scope
+    pub fn is_type_declared(&self, name: &'a str) -> bool {
+        self.types.last().unwrap().contains_key(name)
+    }
+
     pub fn push_type(&mut self, name: &'a str, data_type: DataType<'a>) {
         let map = self.types.last_mut().unwrap();
         map.insert(name, data_type);
@@ -86,6 +100,32 @@ impl<'a> Scope<'a> {
         map.insert(name, var);
     }
 
+    /// the value of the enumerator `name`, unless a variable in an inner
+    /// scope hides it
+    pub fn get_constant(&self, name: &'a str) -> Option<i32> {
+        for (variables, constants) in self.variables.iter().zip(&self.constants).rev() {
+            if variables.contains_key(name) {
+                return None;
+            }
+            if let Some(value) = constants.get(name) {
+                return Some(*value);
+            }
+        }
+        None
+    }
+
+    /// true if `name` is already a variable or enumerator of the innermost
+    /// scope
+    pub fn is_declared(&self, name: &'a str) -> bool {
+        self.variables.last().unwrap().contains_key(name)
+            || self.constants.last().unwrap().contains_key(name)
+    }
+
+    pub fn push_constant(&mut self, name: &'a str, value: i32) {
+        let map = self.constants.last_mut().unwrap();
+        map.insert(name, value);
+    }
+
     pub fn get_function(&self, name: &'a str) -> Option<&'a Function<'a>> {
         for map in self.functions.iter().rev() {
             if let Some(data_type) = map.get(name) {
@@ -101,11 +141,30 @@ impl<'a> Scope<'a> {
     }
 }
 
+/// the labels collected for the switch that is currently resolved
+struct SwitchLabels<'a> {
+    data_type: DataType<'a>,
+    cases: Vec<(i64, i32)>,
+    default: Option<i32>,
+}
+
+/// a label of the current function, a goto may use it before it is defined
+struct Label<'a> {
+    index: i32,
+    defined: bool,
+    used_at: Option<SrcLocation<'a>>,
+}
+
 pub struct ScopeBuilder<'a> {
     bump: &'a Bump,
     scope: Scope<'a>,
     current_function: Option<DataType<'a>>,
     loop_label_index: Vec<i32>,
+    // loops and switches, the innermost one is left by `break`
+    break_label_index: Vec<i32>,
+    switches: Vec<SwitchLabels<'a>>,
+    labels: HashMap<&'a str, Label<'a>>,
+    globals: Vec<&'a ResolvedGlobal<'a>>,
     string_index: i32,
     stack_offset: usize,
     stack_scope: Vec<usize>,
@@ -120,6 +179,10 @@ impl<'a> ScopeBuilder<'a> {
             scope: Scope::new(),
             current_function: None,
             loop_label_index: Vec::new(),
+            break_label_index: Vec::new(),
+            switches: Vec::new(),
+            labels: HashMap::new(),
+            globals: Vec::new(),
             stack_offset: 0,
             stack_scope: vec![0],
             label_index: 0,
@@ -175,10 +238,271 @@ impl<'a> ScopeBuilder<'a> {
         self.label_index
     }
 
+    /// the label called `name` in the current function
+    fn get_label(&mut self, name: &'a str) -> &mut Label<'a> {
+        if !self.labels.contains_key(name) {
+            let index = self.next_label_index();
+            self.labels.insert(
+                name,
+                Label {
+                    index,
+                    defined: false,
+                    used_at: None,
+                },
+            );
+        }
+        self.labels.get_mut(name).unwrap()
+    }
+
     pub fn get_string_label(&mut self) -> i32 {
         self.string_index += 1;
         self.string_index
     }
+
+    /// true if `operation` can store a `value` in a `target`. Only numbers
+    /// can be combined, pointers can also be moved with `+=`, `-=`, `++`
+    /// and `--`.
+    fn can_assign(target: DataType<'a>, value: DataType<'a>, operation: AssignOperation) -> bool {
+        match operation {
+            AssignOperation::Assign => target == value || value.can_convert(target),
+            AssignOperation::Compound(BinaryOps::ADD | BinaryOps::SUB)
+            | AssignOperation::Postfix(_) => {
+                (target.is_number() || matches!(target, DataType::PTR(_))) && value.is_number()
+            }
+            AssignOperation::Compound(_) => target.is_number() && value.is_number(),
+        }
+    }
+
+    /// declares a global or static variable. Its initialiser has to be
+    /// known before the program runs, so it goes into the data sections.
+    fn static_variable(
+        &mut self,
+        name: &'a str,
+        mut global: ResolvedGlobal<'a>,
+        is_const: bool,
+        assignment: Option<&'a Assignment<'a>>,
+    ) -> Result<(), Error<'a>> {
+        if let Some(assignment) = assignment {
+            self.static_value(name, assignment.value, global.data_type, 0, &mut global.values)?;
+        }
+        let zero = global
+            .values
+            .iter()
+            .all(|(_, value)| matches!(value, StaticValue::Integer { value: 0, .. }));
+        let addresses = global
+            .values
+            .iter()
+            .any(|(_, value)| !matches!(value, StaticValue::Integer { .. }));
+        global.section = match (zero, is_const, addresses) {
+            (true, _, _) => Section::Bss,
+            (false, true, true) => Section::DataRelRo,
+            (false, true, false) => Section::Rodata,
+            (false, false, _) => Section::Data,
+        };
+        let mut variable = Variable::at_label(global.label, global.data_type);
+        variable.is_const = is_const;
+        self.scope.push_variable(name, variable);
+        let global = &*self.alloc(global);
+        self.globals.push(global);
+        Ok(())
+    }
+
+    /// adds the initial `value` of a static variable at `offset` to `values`
+    fn static_value(
+        &mut self,
+        name: &'a str,
+        value: &'a Expression<'a>,
+        data_type: DataType<'a>,
+        offset: usize,
+        values: &mut Vec<(usize, StaticValue<'a>)>,
+    ) -> Result<(), Error<'a>> {
+        match (value, data_type) {
+            (Expression::StructExpresion(expression), DataType::Struct(struct_)) => {
+                for (field, field_value) in &expression.fields {
+                    let (field_offset, field_type) = match struct_.field(field) {
+                        Some(x) => x,
+                        None => {
+                            return Err(Error::UnknownField {
+                                expression: value,
+                                name: field,
+                            })
+                        }
+                    };
+                    let field_offset = offset + field_offset;
+                    self.static_value(name, field_value, field_type, field_offset, values)?;
+                }
+                return Ok(());
+            }
+            (
+                Expression::ArrayExpression(ArrayExpression::StackArray { expressions }),
+                DataType::Array(element, length),
+            ) => {
+                if expressions.len() > length {
+                    return Err(Error::TooManyInitializers { expression: value });
+                }
+                for (index, element_value) in expressions.iter().enumerate() {
+                    let element_offset = offset + index * element.size();
+                    self.static_value(name, element_value, *element, element_offset, values)?;
+                }
+                return Ok(());
+            }
+            (
+                Expression::ArrayExpression(ArrayExpression::StringLiteral { string }),
+                DataType::Array(DataType::CHAR, length),
+            ) => {
+                if string.len() > length {
+                    return Err(Error::TooManyInitializers { expression: value });
+                }
+                for (index, c) in string.iter().enumerate() {
+                    let value = StaticValue::Integer {
+                        value: *c as i8 as i64,
+                        size: 1,
+                    };
+                    values.push((offset + index, value));
+                }
+                return Ok(());
+            }
+            _ => (),
+        }
+        let resolved = value.accept(self)?;
+        if !Self::can_assign(data_type, resolved.data_type(), AssignOperation::Assign) {
+            return Err(Error::VariableInitWrong {
+                expected: data_type,
+                found: resolved.data_type(),
+                name,
+            });
+        }
+        let value = match (resolved, resolved.constant_value()) {
+            (
+                ResolvedExpression::ArrayExpression(ResolvedArrayExpression::StringLiteral {
+                    string,
+                    string_label_index,
+                    ..
+                }),
+                _,
+            ) => StaticValue::String {
+                string,
+                label_index: *string_label_index,
+            },
+            (_, Some(value)) => StaticValue::Integer {
+                value: data_type.truncate(value),
+                size: data_type.size(),
+            },
+            (_, None) => match resolved.static_pointer() {
+                Some((label, offset)) if matches!(data_type, DataType::PTR(_)) => {
+                    StaticValue::Address { label, offset }
+                }
+                _ => return Err(Error::StaticInitializerNotConstant { name }),
+            },
+        };
+        values.push((offset, value));
+        Ok(())
+    }
+
+    /// resolves `value` that is stored in a `data_type`. A struct expression
+    /// is laid out like the struct it initialises, so its fields can come
+    /// in any order and a union is initialised through one of its fields.
+    fn initializer(
+        &mut self,
+        value: &'a Expression<'a>,
+        data_type: DataType<'a>,
+    ) -> Result<&'a ResolvedExpression<'a>, Error<'a>> {
+        let mut values = Vec::new();
+        match (value, data_type) {
+            (Expression::StructExpresion(expression), DataType::Struct(struct_)) => {
+                for (name, field_value) in &expression.fields {
+                    let (offset, field_type) = match struct_.field(name) {
+                        Some(field) => field,
+                        None => {
+                            return Err(Error::UnknownField {
+                                expression: value,
+                                name,
+                            })
+                        }
+                    };
+                    let resolved = self.initializer(field_value, field_type)?;
+                    if !Self::can_assign(field_type, resolved.data_type(), AssignOperation::Assign)
+                    {
+                        return Err(Error::CannotAssign {
+                            from: field_value,
+                            to: value,
+                        });
+                    }
+                    values.push((offset, field_type, resolved));
+                }
+            }
+            (
+                Expression::ArrayExpression(ArrayExpression::StackArray { expressions }),
+                DataType::Array(element, length),
+            ) => {
+                if expressions.len() > length {
+                    return Err(Error::TooManyInitializers { expression: value });
+                }
+                for (index, element_value) in expressions.iter().enumerate() {
+                    let resolved = self.initializer(element_value, *element)?;
+                    if !Self::can_assign(*element, resolved.data_type(), AssignOperation::Assign) {
+                        return Err(Error::CannotAssign {
+                            from: element_value,
+                            to: value,
+                        });
+                    }
+                    values.push((index * element.size(), *element, resolved));
+                }
+            }
+            // the terminating zero is left out if it does not fit
+            (
+                Expression::ArrayExpression(ArrayExpression::StringLiteral { string }),
+                DataType::Array(DataType::CHAR, length),
+            ) => {
+                if string.len() > length {
+                    return Err(Error::TooManyInitializers { expression: value });
+                }
+                for (index, c) in string.iter().enumerate() {
+                    let resolved = &*self.alloc(ResolvedExpression::CharLiteral(*c));
+                    values.push((index, DataType::CHAR, resolved));
+                }
+            }
+            _ => {
+                let resolved = value.accept(self)?;
+                if data_type.is_scalar()
+                    && Self::can_assign(data_type, resolved.data_type(), AssignOperation::Assign)
+                {
+                    return Ok(self.convert(resolved, data_type));
+                }
+                return Ok(resolved);
+            }
+        }
+        // the values may need stack space themselves, so the struct is
+        // placed after them
+        self.stack_offset += data_type.size();
+        let stack_offset = self.stack_offset;
+        let mut fields = Vec::new();
+        for (offset, data_type, value) in values {
+            let variable = Variable::new(stack_offset - offset, data_type);
+            let assignment = ResolvedAssignment {
+                target: self.alloc(ResolvedExpression::NamedVariable { variable }),
+                value,
+                operation: AssignOperation::Assign,
+            };
+            fields.push(&*self.alloc(assignment));
+        }
+        let resolved = match data_type {
+            DataType::Array(..) => {
+                let array = ResolvedArrayExpression::StackArray {
+                    expressions: fields,
+                    data_type,
+                    stack_offset,
+                };
+                ResolvedExpression::ArrayExpression(self.alloc(array))
+            }
+            _ => ResolvedExpression::StructExpresion(self.alloc(ResolvedStructExpression {
+                fields,
+                data_type,
+                stack_offset,
+            })),
+        };
+        Ok(self.alloc(resolved))
+    }
 }
 
 impl<'a> Visitor<&Program<'a>, Result<&'a ResolvedProgram<'a>, Error<'a>>> for ScopeBuilder<'a> {
@@ -192,7 +516,8 @@ impl<'a> Visitor<&Program<'a>, Result<&'a ResolvedProgram<'a>, Error<'a>>> for S
                 Decalrations::Function(f) => functions.push(f.accept(self)?),
             };
         }
-        Ok(self.alloc(ResolvedProgram { functions }))
+        let globals = std::mem::take(&mut self.globals);
+        Ok(self.alloc(ResolvedProgram { functions, globals }))
     }
 }
 
@@ -227,6 +552,7 @@ impl<'a> Visitor<&'a Function<'a>, Result<&'a ResolvedFunction<'a>, Error<'a>>>
         };
         self.push_function(visitor.name, visitor);
         self.current_function = Some(return_type);
+        self.labels.clear();
         self.push();
 
         let mut parameter = Vec::new();
@@ -246,6 +572,18 @@ impl<'a> Visitor<&'a Function<'a>, Result<&'a ResolvedFunction<'a>, Error<'a>>>
                 self.pop();
             }
         };
+        self.current_function = None;
+        let undefined = self
+            .labels
+            .iter()
+            .filter(|(_, label)| !label.defined)
+            .min_by_key(|(_, label)| label.index);
+        if let Some((name, label)) = undefined {
+            return Err(Error::UndefinedLabel {
+                name,
+                location: label.used_at.unwrap(),
+            });
+        }
         Ok(self.alloc(ResolvedFunction {
             name: visitor.name,
             parameter,
@@ -262,7 +600,10 @@ impl<'a> Visitor<&Statement<'a>, Result<&'a ResolvedStatement<'a>, Error<'a>>>
         Ok(self.bump.alloc(match visitor {
             Statement::Return(x) => match x {
                 Some(expr) => {
-                    let expr_type = expr.accept(self)?;
+                    let expr_type = match self.current_function {
+                        Some(f) => self.initializer(expr, f)?,
+                        None => expr.accept(self)?,
+                    };
                     match self.current_function {
                         Some(f) => {
                             if f == expr_type.data_type() {
@@ -301,6 +642,80 @@ impl<'a> Visitor<&Statement<'a>, Result<&'a ResolvedStatement<'a>, Error<'a>>>
             Statement::WhileStatement(while_statement) => {
                 ResolvedStatement::WhileStatement(while_statement.accept(self)?)
             }
+            Statement::SwitchStatement(switch_statement) => {
+                ResolvedStatement::SwitchStatement(switch_statement.accept(self)?)
+            }
+            Statement::DoWhileStatement(do_while_statement) => {
+                ResolvedStatement::DoWhileStatement(do_while_statement.accept(self)?)
+            }
+            Statement::Label { name, location } => {
+                let label = self.get_label(name);
+                if label.defined {
+                    return Err(Error::DuplicateLabel {
+                        name,
+                        location: *location,
+                    });
+                }
+                label.defined = true;
+                ResolvedStatement::Label(label.index)
+            }
+            Statement::StaticAssert {
+                condition,
+                message,
+                location,
+            } => match condition.accept(self)?.constant_value() {
+                Some(0) => {
+                    return Err(Error::StaticAssertionFailed {
+                        message: *message,
+                        location: *location,
+                    })
+                }
+                Some(_) => ResolvedStatement::Empty,
+                None => {
+                    return Err(Error::StaticAssertNotConstant {
+                        location: *location,
+                    })
+                }
+            },
+            Statement::Goto { name, location } => {
+                let label = self.get_label(name);
+                if label.used_at.is_none() {
+                    label.used_at = Some(*location);
+                }
+                ResolvedStatement::Goto(label.index)
+            }
+            Statement::Case { value, location } => {
+                let resolved = value.accept(self)?;
+                let label_index = self.next_label_index();
+                let switch = match self.switches.last_mut() {
+                    Some(switch) => switch,
+                    None => return Err(Error::CaseNotInSwitch { location: *location }),
+                };
+                let value = match resolved.constant_value() {
+                    Some(value) => switch.data_type.truncate(value),
+                    None => return Err(Error::CaseLabelNotConstant { location: *location }),
+                };
+                if switch.cases.iter().any(|(x, _)| *x == value) {
+                    return Err(Error::DuplicateCaseLabel {
+                        value,
+                        location: *location,
+                    });
+                }
+                switch.cases.push((value, label_index));
+                ResolvedStatement::Case(label_index)
+            }
+            Statement::Default { location } => {
+                let label_index = self.next_label_index();
+                let switch = match self.switches.last_mut() {
+                    Some(switch) => switch,
+                    None => return Err(Error::CaseNotInSwitch { location: *location }),
+                };
+                if switch.default.is_some() {
+                    return Err(Error::DuplicateDefault { location: *location });
+                }
+                switch.default = Some(label_index);
+                ResolvedStatement::Case(label_index)
+            }
             Statement::TypeDefinition(x) => {
                 x.accept(self)?;
                 ResolvedStatement::Empty
@@ -309,21 +724,75 @@ impl<'a> Visitor<&Statement<'a>, Result<&'a ResolvedStatement<'a>, Error<'a>>>
                 name,
                 expression,
                 assignment,
-            } => match self.get_variable(name) {
-                Some(_) => return Err(Error::VariableRedefinition { name: name }),
-                None => {
-                    if self.current_function.is_none() {
-                        return Err(Error::VariableDeclarationOutsideOfFunction { name: name });
-                    }
-                    let type_ = expression.accept(self)?;
-                    if type_.size() == 0 {
-                        return Err(Error::VariableOfUnkownSize {
-                            name: name,
-                            data_type: type_,
-                        });
-                    }
+                is_static,
+                is_const,
+            } => {
+                if self.scope.is_declared(name) {
+                    return Err(Error::VariableRedefinition { name });
+                }
+                let mut type_ = expression.accept(self)?;
+                // `int a[] = {1, 2}` takes its length from the initialiser
+                if let (DataType::Array(element, 0), Some(assignment)) = (type_, assignment) {
+                    let length = match assignment.value {
+                        Expression::ArrayExpression(ArrayExpression::StackArray {
+                            expressions,
+                        }) => expressions.len(),
+                        Expression::ArrayExpression(ArrayExpression::StringLiteral { string }) => {
+                            string.len() + 1
+                        }
+                        _ => 0,
+                    };
+                    type_ = DataType::Array(element, length);
+                }
+                if type_.size() == 0 {
+                    return Err(Error::VariableOfUnkownSize {
+                        name,
+                        data_type: type_,
+                    });
+                }
+                if self.current_function.is_none() || *is_static {
+                    // statics inside of functions get a label of their own
+                    let label = match self.current_function {
+                        None => *name,
+                        Some(_) => {
+                            let index = self.next_label_index();
+                            self.alloc(format!("{}.{}", name, index)).as_str()
+                        }
+                    };
+                    let exported = self.current_function.is_none() && !is_static;
+                    let global = ResolvedGlobal {
+                        label,
+                        data_type: type_,
+                        exported,
+                        section: Section::Bss,
+                        values: Vec::new(),
+                    };
+                    self.static_variable(name, global, *is_const, *assignment)?;
+                    ResolvedStatement::Empty
+                } else {
                     let offset = self.push_variable(name, type_);
-                    match assignment {
+                    let statement = match assignment {
+                        // arrays cannot be assigned to, only initialised
+                        Some(x) if matches!(type_, DataType::Array(..)) => {
+                            let variable = self.get_variable(name).unwrap();
+                            let value = self.initializer(x.value, type_)?;
+                            if value.data_type() != type_ {
+                                return Err(Error::VariableInitWrong {
+                                    expected: type_,
+                                    found: value.data_type(),
+                                    name,
+                                });
+                            }
+                            let assignment = ResolvedAssignment {
+                                target: self.alloc(ResolvedExpression::NamedVariable { variable }),
+                                value,
+                                operation: AssignOperation::Assign,
+                            };
+                            ResolvedStatement::VariableDeclaration {
+                                stack_offset: offset,
+                                assignment: Some(self.alloc(assignment)),
+                            }
+                        }
                         Some(x) => {
                             let resolved_expr = x.accept(self)?;
                             if resolved_expr.data_type() != type_ {
@@ -342,18 +811,25 @@ impl<'a> Visitor<&Statement<'a>, Result<&'a ResolvedStatement<'a>, Error<'a>>>
                             stack_offset: offset,
                             assignment: None,
                         },
+                    };
+                    // only the initialiser may write to a const variable
+                    if *is_const {
+                        let mut variable = self.get_variable(name).unwrap();
+                        variable.is_const = true;
+                        self.scope.push_variable(name, variable);
                     }
+                    statement
                 }
-            },
+            }
             Statement::Conitnue => {
-                let label_index = self.loop_label_index.pop();
+                let label_index = self.loop_label_index.last().copied();
                 if label_index.is_none() {
                     return Err(Error::ContinueNotInLoop {});
                 }
                 ResolvedStatement::Continue(label_index.unwrap())
             }
             Statement::Break => {
-                let label_index = self.loop_label_index.pop();
+                let label_index = self.break_label_index.last().copied();
                 if label_index.is_none() {
                     return Err(Error::BreakNotInLoop {});
                 }
@@ -381,11 +857,13 @@ impl<'a> Visitor<&WhileStatement<'a>, Result<&'a ResolvedWhileStatement<'a>, Err
     ) -> Result<&'a ResolvedWhileStatement<'a>, Error<'a>> {
         let label_index = self.next_label_index();
         self.loop_label_index.push(label_index);
+        self.break_label_index.push(label_index);
 
         let condition = visitor.condition.accept(self)?;
         let body = visitor.body.accept(self)?;
 
         self.loop_label_index.pop();
+        self.break_label_index.pop();
 
         Ok(self.alloc(ResolvedWhileStatement {
             condition,
@@ -395,6 +873,69 @@ impl<'a> Visitor<&WhileStatement<'a>, Result<&'a ResolvedWhileStatement<'a>, Err
     }
 }
 
+impl<'a> Visitor<&DoWhileStatement<'a>, Result<&'a ResolvedDoWhileStatement<'a>, Error<'a>>>
+    for ScopeBuilder<'a>
+{
+    fn visit(
+        &mut self,
+        visitor: &DoWhileStatement<'a>,
+    ) -> Result<&'a ResolvedDoWhileStatement<'a>, Error<'a>> {
+        let label_index = self.next_label_index();
+        self.loop_label_index.push(label_index);
+        self.break_label_index.push(label_index);
+
+        let body = visitor.body.accept(self)?;
+        let condition = visitor.condition.accept(self)?;
+
+        self.loop_label_index.pop();
+        self.break_label_index.pop();
+
+        Ok(self.alloc(ResolvedDoWhileStatement {
+            body,
+            condition,
+            label_index,
+        }))
+    }
+}
+
+impl<'a> Visitor<&SwitchStatement<'a>, Result<&'a ResolvedSwitchStatement<'a>, Error<'a>>>
+    for ScopeBuilder<'a>
+{
+    fn visit(
+        &mut self,
+        visitor: &SwitchStatement<'a>,
+    ) -> Result<&'a ResolvedSwitchStatement<'a>, Error<'a>> {
+        let condition = visitor.condition.accept(self)?;
+        if !condition.data_type().is_number() {
+            return Err(Error::SwitchOnNonNumber {
+                expression: visitor.condition,
+            });
+        }
+        let condition = self.promote(condition);
+
+        let label_index = self.next_label_index();
+        self.break_label_index.push(label_index);
+        self.switches.push(SwitchLabels {
+            data_type: condition.data_type(),
+            cases: Vec::new(),
+            default: None,
+        });
+
+        let body = visitor.body.accept(self)?;
+
+        self.break_label_index.pop();
+        let labels = self.switches.pop().unwrap();
+
+        Ok(self.alloc(ResolvedSwitchStatement {
+            condition,
+            body,
+            cases: labels.cases,
+            default: labels.default,
+            label_index,
+        }))
+    }
+}
+
 impl<'a> Visitor<&ForStatement<'a>, Result<&'a ResolvedForStatement<'a>, Error<'a>>>
     for ScopeBuilder<'a>
 {
@@ -406,6 +947,7 @@ impl<'a> Visitor<&ForStatement<'a>, Result<&'a ResolvedForStatement<'a>, Error<'
 
         let label_index = self.next_label_index();
         self.loop_label_index.push(label_index);
+        self.break_label_index.push(label_index);
 
         let init = visitor.init.accept(self)?;
         let condition = visitor.condition.accept(self)?;
@@ -417,6 +959,7 @@ impl<'a> Visitor<&ForStatement<'a>, Result<&'a ResolvedForStatement<'a>, Error<'
         let body = visitor.body.accept(self)?;
 
         self.loop_label_index.pop();
+        self.break_label_index.pop();
         self.pop();
         Ok(self.alloc(ResolvedForStatement {
             init,
@@ -473,65 +1016,120 @@ impl<'a> Visitor<&Compound<'a>, Result<&'a ResolvedCompound<'a>, Error<'a>>> for
 impl<'a> Visitor<&TypeExpression<'a>, Result<DataType<'a>, Error<'a>>> for ScopeBuilder<'a> {
     fn visit(&mut self, visitor: &TypeExpression<'a>) -> Result<DataType<'a>, Error<'a>> {
         let data_type = match visitor {
+            TypeExpression::Integer { kind, unsigned } => match (kind, unsigned) {
+                (TokenKind::BOOL, _) => DataType::BOOL,
+                (TokenKind::CHAR, false) => DataType::CHAR,
+                (TokenKind::CHAR, true) => DataType::UCHAR,
+                (TokenKind::SHORT, false) => DataType::SHORT,
+                (TokenKind::SHORT, true) => DataType::USHORT,
+                (TokenKind::LONG, false) => DataType::LONG,
+                (TokenKind::LONG, true) => DataType::ULONG,
+                (_, false) => DataType::INT,
+                (_, true) => DataType::UINT,
+            },
             TypeExpression::Primitive(x) => match x {
-                TokenKind::INT => DataType::INT,
-                TokenKind::CHAR => DataType::CHAR,
-                TokenKind::LONG => DataType::LONG,
                 TokenKind::VOID => DataType::VOID,
                 _ => panic!(
                     "This should not happen! Cannot resolve data-type for token: {:?}",
                     x
                 ),
             },
-            TypeExpression::Typeof(e) => e.accept(self)?.data_type(),
+            TypeExpression::Typeof(e) => self.resolve_expression(e)?.data_type(),
             TypeExpression::Named(name) => match self.get_type(name) {
                 Some(x) => x,
                 None => return Err(Error::UnknownType { type_name: name }),
             },
-            TypeExpression::Struct { name, fields } => match self.get_type(name) {
-                Some(x) if x == DataType::EmptyStruct => {
-                    let mut resolved_fields = Vec::new();
+            TypeExpression::Struct {
+                name,
+                fields,
+                is_union,
+            } => {
+                let struct_name = match (name, is_union) {
+                    (Some(name), _) => name,
+                    (None, true) => "union",
+                    (None, false) => "struct",
+                };
+                // a struct declared in the same block is completed by its
+                // definition, one of an outer block is hidden
+                let declared = name
+                    .filter(|name| self.scope.is_type_declared(name))
+                    .and_then(|name| self.get_type(name));
+                let struct_ = match declared {
+                    Some(DataType::Struct(x)) if !x.is_complete() => x,
+                    Some(_) => return Err(Error::StructRedefinition { name: struct_name }),
+                    None => {
+                        let struct_ = &*self.alloc(Struct::declare(struct_name, *is_union));
+                        if let Some(name) = name {
+                            self.push_type(name, DataType::Struct(struct_));
+                        }
+                        struct_
+                    }
+                };
+                let mut resolved_fields = Vec::new();
 
-                    for (name, type_expr) in fields {
-                        resolved_fields.push((*name, type_expr.accept(self)?))
+                for (field_name, type_expr) in fields {
+                    if fields.iter().filter(|x| x.0 == *field_name).count() != 1 {
+                        return Err(Error::StructFieldRedefinition {
+                            struct_name,
+                            field_name,
+                        });
                     }
-                    let struct_ = Struct::new(resolved_fields);
-                    let struct_ = self.alloc(struct_);
-                    DataType::Struct(struct_)
+                    let type_ = type_expr.accept(self)?;
+                    if type_.size() == 0 {
+                        return Err(Error::StructFieldUnkownSize {
+                            struct_name,
+                            field_name,
+                        });
+                    }
+                    resolved_fields.push((*field_name, type_))
                 }
-                Some(_) => return Err(Error::StructRedefinition { name: name }),
-                None => {
-                    self.push_type(name, DataType::EmptyStruct);
-                    let mut resolved_fields = Vec::new();
-
-                    for (field_name, type_expr) in fields {
-                        if fields.iter().filter(|x| x.0 == *field_name).count() != 1 {
-                            return Err(Error::StructFieldRedefinition {
-                                struct_name: name,
-                                field_name,
-                            });
-                        }
-                        let type_ = type_expr.accept(self)?;
-                        if type_.size() == 0 {
-                            return Err(Error::StructFieldUnkownSize {
-                                struct_name: name,
-                                field_name,
-                            });
-                        }
-                        resolved_fields.push((*field_name, type_))
+                // the struct may have been defined while resolving its fields
+                if !struct_.define(resolved_fields) {
+                    return Err(Error::StructRedefinition { name: struct_name });
+                }
+                DataType::Struct(struct_)
+            }
+            TypeExpression::Enum { name, enumerators } => {
+                // an enum in an inner block may hide one of an outer block
+                if let Some(name) = name {
+                    if self.scope.is_type_declared(name) {
+                        return Err(Error::EnumRedefinition { name });
                     }
-                    let struct_ = Struct::new(resolved_fields);
-                    let struct_ = self.alloc(struct_);
-                    let type_ = DataType::Struct(struct_);
-                    self.push_type(name, type_);
-                    DataType::Struct(struct_)
+                    self.push_type(name, DataType::INT);
                 }
+                let mut next: i64 = 0;
+                for (name, value) in enumerators {
+                    if self.scope.is_declared(name) {
+                        return Err(Error::EnumeratorRedefinition { name });
+                    }
+                    if let Some(value) = value {
+                        let resolved = value.accept(self)?;
+                        next = match resolved.constant_value() {
+                            Some(value) => value,
+                            None => return Err(Error::EnumeratorNotConstant { name }),
+                        };
+                    }
+                    // every enumerator is an int
+                    match i32::try_from(next) {
+                        Ok(value) => self.scope.push_constant(name, value),
+                        Err(_) => return Err(Error::EnumeratorOutOfRange { name }),
+                    }
+                    next += 1;
+                }
+                DataType::INT
+            }
+            TypeExpression::NamedEnum(name) => match self.get_type(name) {
+                Some(x) => x,
+                None => return Err(Error::UnknownType { type_name: name }),
             },
+            // an unknown struct is declared, it can be defined later
             TypeExpression::NamedStruct(name) => match self.get_type(name) {
                 Some(x) => x,
                 None => {
-                    self.push_type(name, DataType::EmptyStruct);
-                    DataType::EmptyStruct
+                    let is_union = name.starts_with("union ");
+                    let struct_ = DataType::Struct(self.alloc(Struct::declare(name, is_union)));
+                    self.push_type(name, struct_);
+                    struct_
                 }
             },
             TypeExpression::Pointer(expr) => {
@@ -539,6 +1137,18 @@ impl<'a> Visitor<&TypeExpression<'a>, Result<DataType<'a>, Error<'a>>> for Scope
                 let base = self.alloc(resolved);
                 DataType::PTR(base)
             }
+            TypeExpression::Array { element, length } => {
+                let element = element.accept(self)?;
+                let length = match length {
+                    Some(length) => match length.accept(self)?.constant_value() {
+                        Some(x) if x < 0 => return Err(Error::ArraySizeNegative { length }),
+                        Some(x) => x as usize,
+                        None => return Err(Error::ArraySizeNotConstant { length }),
+                    },
+                    None => 0,
+                };
+                DataType::Array(self.alloc(element), length)
+            }
         };
         Ok(data_type)
     }
@@ -548,8 +1158,83 @@ impl<'a> Visitor<&Expression<'a>, Result<&'a ResolvedExpression<'a>, Error<'a>>>
     for ScopeBuilder<'a>
 {
     fn visit(&mut self, visitor: &Expression<'a>) -> Result<&'a ResolvedExpression<'a>, Error<'a>> {
+        let resolved = self.resolve_expression(visitor)?;
+        Ok(self.decay(resolved))
+    }
+}
+
+impl<'a> ScopeBuilder<'a> {
+    /// an array used as a value is a pointer to its first element
+    fn decay(&self, expression: &'a ResolvedExpression<'a>) -> &'a ResolvedExpression<'a> {
+        match expression.data_type() {
+            DataType::Array(element, _) => self.alloc(ResolvedExpression::Cast {
+                expression,
+                data_type: DataType::PTR(element),
+            }),
+            _ => expression,
+        }
+    }
+
+    /// converts a value to another type through an implicit cast
+    fn convert(
+        &self,
+        expression: &'a ResolvedExpression<'a>,
+        data_type: DataType<'a>,
+    ) -> &'a ResolvedExpression<'a> {
+        if expression.data_type() == data_type {
+            return expression;
+        }
+        self.alloc(ResolvedExpression::Cast {
+            expression,
+            data_type,
+        })
+    }
+
+    /// the number of bytes a pointer to `element` moves per element, gcc
+    /// steps over void like over char
+    fn element_size(element: DataType<'a>) -> Result<i64, Error<'a>> {
+        match element {
+            DataType::VOID => Ok(1),
+            x if !x.is_complete() => Err(Error::SizeOfIncompleteType { data_type: x }),
+            x => Ok(x.size() as i64),
+        }
+    }
+
+    /// turns a number of elements into the number of bytes a pointer to
+    /// `element` moves by
+    fn scale(
+        &self,
+        expression: &'a ResolvedExpression<'a>,
+        element: DataType<'a>,
+    ) -> Result<&'a ResolvedExpression<'a>, Error<'a>> {
+        let expression = self.convert(expression, DataType::LONG);
+        Ok(match Self::element_size(element)? {
+            1 => expression,
+            size => self.alloc(ResolvedExpression::BinaryExpression {
+                lhs: expression,
+                rhs: self.alloc(ResolvedExpression::LongLiteral(size)),
+                operation: BinaryOps::MUL,
+                resulting_type: DataType::LONG,
+            }),
+        })
+    }
+
+    /// integer promotion, small integers are calculated as int
+    fn promote(&self, expression: &'a ResolvedExpression<'a>) -> &'a ResolvedExpression<'a> {
+        self.convert(expression, expression.data_type().promote())
+    }
+
+    /// resolves an expression without decaying arrays, `sizeof`, `&` and
+    /// `typeof` see the array itself
+    fn resolve_expression(
+        &mut self,
+        visitor: &Expression<'a>,
+    ) -> Result<&'a ResolvedExpression<'a>, Error<'a>> {
         Ok(self.bump.alloc(match visitor {
             Expression::IntLiteral(i) => ResolvedExpression::IntLiteral(*i),
+            Expression::UIntLiteral(i) => ResolvedExpression::UIntLiteral(*i),
+            Expression::LongLiteral(i) => ResolvedExpression::LongLiteral(*i),
+            Expression::ULongLiteral(i) => ResolvedExpression::ULongLiteral(*i),
             Expression::CharLiteral(c) => ResolvedExpression::CharLiteral(*c),
             Expression::FunctionCall(function_call) => {
                 ResolvedExpression::FunctionCall(function_call.accept(self)?)
@@ -564,7 +1249,21 @@ impl<'a> Visitor<&Expression<'a>, Result<&'a ResolvedExpression<'a>, Error<'a>>>
                 ResolvedExpression::Assignment(assignment.accept(self)?)
             }
             Expression::TypeExpression(t) => ResolvedExpression::TypeExpression(t.accept(self)?),
-            Expression::SizeOf(x) => ResolvedExpression::SizeOf(x.accept(self)?.data_type().size()),
+            Expression::SizeOf(x) => {
+                let data_type = self.resolve_expression(x)?.data_type();
+                if !data_type.is_complete() {
+                    return Err(Error::SizeOfIncompleteType { data_type });
+                }
+                ResolvedExpression::SizeOf(data_type.size())
+            }
+            // an int constant like `sizeof`
+            Expression::AlignOf(x) => {
+                let data_type = self.resolve_expression(x)?.data_type();
+                if !data_type.is_complete() {
+                    return Err(Error::SizeOfIncompleteType { data_type });
+                }
+                ResolvedExpression::SizeOf(data_type.alignment())
+            }
             Expression::FieldAccess { name, operand } => {
                 let resolved_operand = operand.accept(self)?;
                 match resolved_operand.data_type() {
@@ -623,13 +1322,16 @@ impl<'a> Visitor<&Expression<'a>, Result<&'a ResolvedExpression<'a>, Error<'a>>>
 
                 match resolved_operand.data_type() {
                     DataType::PTR(x) => ResolvedExpression::Indexing {
-                        index: resolved_index,
+                        index: self.convert(resolved_index, DataType::LONG),
                         operand: resolved_operand,
                         data_type: *x,
                     },
                     _ => return Err(Error::DerefOfNonPointer { expr: operand }),
                 }
             }
+            Expression::NamedVariable { name } if self.scope.get_constant(name).is_some() => {
+                ResolvedExpression::IntLiteral(self.scope.get_constant(name).unwrap())
+            }
             Expression::NamedVariable { name } => match self.get_variable(name) {
                 Some(v) => ResolvedExpression::NamedVariable { variable: v },
                 None => return Err(Error::UnknownVariable { name }),
@@ -639,9 +1341,11 @@ impl<'a> Visitor<&Expression<'a>, Result<&'a ResolvedExpression<'a>, Error<'a>>>
                 operation,
             } => match operation {
                 UnaryOps::REF => {
-                    //TODO: have to check if its an l-value
-                    // i don't know how yet
-                    let expression = expression.accept(self)?;
+                    let resolved_expression = self.resolve_expression(expression)?;
+                    if !resolved_expression.is_lvalue() {
+                        return Err(Error::AddressOfNonLvalue { expression });
+                    }
+                    let expression = resolved_expression;
                     ResolvedExpression::Unary {
                         expression: expression,
                         operation: *operation,
@@ -675,10 +1379,18 @@ impl<'a> Visitor<&Expression<'a>, Result<&'a ResolvedExpression<'a>, Error<'a>>>
                             operation: *operation,
                         });
                     }
+                    // `!` gives an int, `-` and `~` calculate in the promoted type
+                    let (expression, resulting_type) = match operation {
+                        UnaryOps::LOGNEG => (resolved_expression, DataType::INT),
+                        _ => {
+                            let promoted = self.promote(resolved_expression);
+                            (promoted, promoted.data_type())
+                        }
+                    };
                     ResolvedExpression::Unary {
-                        expression: resolved_expression,
+                        expression,
                         operation: *operation,
-                        resulting_type: resolved_expression.data_type(),
+                        resulting_type,
                     }
                 }
             },
@@ -687,18 +1399,142 @@ impl<'a> Visitor<&Expression<'a>, Result<&'a ResolvedExpression<'a>, Error<'a>>>
                 rhs,
                 operation,
             } => {
-                let resolved_lhs = lhs.accept(self)?;
-                let resolved_rhs = rhs.accept(self)?;
+                let mut resolved_lhs = lhs.accept(self)?;
+                let mut resolved_rhs = rhs.accept(self)?;
                 let lhs_data = resolved_lhs.data_type();
                 let rhs_data = resolved_rhs.data_type();
-                if lhs_data != rhs_data && !lhs_data.can_convert(rhs_data) {
-                    return Err(Error::OperandsDifferentDatatypes { lhs: lhs, rhs: rhs });
-                }
+                let resulting_type = match operation {
+                    // the comma operator only keeps the value of the right side
+                    BinaryOps::COMMA => rhs_data,
+                    BinaryOps::AND | BinaryOps::OR => {
+                        for (expression, data_type) in [(lhs, lhs_data), (rhs, rhs_data)] {
+                            if !data_type.is_scalar() {
+                                return Err(Error::BinaryOperandNotNumber {
+                                    expression,
+                                    operation: *operation,
+                                });
+                            }
+                        }
+                        DataType::INT
+                    }
+                    BinaryOps::BITAND
+                    | BinaryOps::BITOR
+                    | BinaryOps::XOR
+                    | BinaryOps::SHL
+                    | BinaryOps::SHR => {
+                        for (expression, data_type) in [(lhs, lhs_data), (rhs, rhs_data)] {
+                            if !data_type.is_number() {
+                                return Err(Error::BinaryOperandNotNumber {
+                                    expression,
+                                    operation: *operation,
+                                });
+                            }
+                        }
+                        // a shift has the type of the promoted shifted value
+                        if matches!(operation, BinaryOps::SHL | BinaryOps::SHR) {
+                            resolved_lhs = self.promote(resolved_lhs);
+                            resolved_rhs = self.promote(resolved_rhs);
+                            resolved_lhs.data_type()
+                        } else {
+                            let common = lhs_data.common(rhs_data);
+                            resolved_lhs = self.convert(resolved_lhs, common);
+                            resolved_rhs = self.convert(resolved_rhs, common);
+                            common
+                        }
+                    }
+                    // pointer arithmetic counts elements instead of bytes
+                    BinaryOps::ADD | BinaryOps::SUB
+                        if !lhs_data.is_number() || !rhs_data.is_number() =>
+                    {
+                        match (lhs_data, rhs_data) {
+                            (DataType::PTR(element), x) if x.is_number() => {
+                                resolved_rhs = self.scale(resolved_rhs, *element)?;
+                                lhs_data
+                            }
+                            (x, DataType::PTR(element))
+                                if x.is_number() && *operation == BinaryOps::ADD =>
+                            {
+                                resolved_lhs = self.scale(resolved_lhs, *element)?;
+                                rhs_data
+                            }
+                            // the difference of two pointers is divided by
+                            // the size of their elements
+                            (DataType::PTR(element), DataType::PTR(other))
+                                if *operation == BinaryOps::SUB && element == other =>
+                            {
+                                let size = Self::element_size(*element)?;
+                                let difference = self.alloc(ResolvedExpression::BinaryExpression {
+                                    lhs: self.convert(resolved_lhs, DataType::LONG),
+                                    rhs: self.convert(resolved_rhs, DataType::LONG),
+                                    operation: BinaryOps::SUB,
+                                    resulting_type: DataType::LONG,
+                                });
+                                return Ok(self.alloc(ResolvedExpression::BinaryExpression {
+                                    lhs: difference,
+                                    rhs: self.alloc(ResolvedExpression::LongLiteral(size)),
+                                    operation: BinaryOps::DIV,
+                                    resulting_type: DataType::LONG,
+                                }));
+                            }
+                            _ => return Err(Error::OperandsDifferentDatatypes { lhs, rhs }),
+                        }
+                    }
+                    _ => {
+                        if lhs_data != rhs_data && !lhs_data.can_convert(rhs_data) {
+                            return Err(Error::OperandsDifferentDatatypes { lhs, rhs });
+                        }
+                        // a number next to a pointer is used as a pointer
+                        let common = match (lhs_data.is_number(), rhs_data.is_number()) {
+                            (true, true) => lhs_data.common(rhs_data),
+                            (true, false) => rhs_data,
+                            _ => lhs_data,
+                        };
+                        resolved_lhs = self.convert(resolved_lhs, common);
+                        resolved_rhs = self.convert(resolved_rhs, common);
+                        match operation {
+                            BinaryOps::EQ
+                            | BinaryOps::NE
+                            | BinaryOps::LT
+                            | BinaryOps::GT
+                            | BinaryOps::LE
+                            | BinaryOps::GE => DataType::INT,
+                            _ => common,
+                        }
+                    }
+                };
                 ResolvedExpression::BinaryExpression {
                     lhs: resolved_lhs,
                     rhs: resolved_rhs,
                     operation: *operation,
-                    resulting_type: resolved_lhs.data_type(),
+                    resulting_type,
+                }
+            }
+            Expression::Ternary {
+                condition,
+                then,
+                otherwise,
+            } => {
+                let resolved_condition = condition.accept(self)?;
+                let resolved_then = then.accept(self)?;
+                let resolved_otherwise = otherwise.accept(self)?;
+                let then_data = resolved_then.data_type();
+                let otherwise_data = resolved_otherwise.data_type();
+                if then_data != otherwise_data && !then_data.can_convert(otherwise_data) {
+                    return Err(Error::OperandsDifferentDatatypes {
+                        lhs: then,
+                        rhs: otherwise,
+                    });
+                }
+                let data_type = match (then_data.is_number(), otherwise_data.is_number()) {
+                    (true, true) => then_data.common(otherwise_data),
+                    (true, false) => otherwise_data,
+                    _ => then_data,
+                };
+                ResolvedExpression::Ternary {
+                    condition: resolved_condition,
+                    then: self.convert(resolved_then, data_type),
+                    otherwise: self.convert(resolved_otherwise, data_type),
+                    data_type,
                 }
             }
         }))
@@ -712,148 +1548,49 @@ impl<'a> Visitor<&'a Assignment<'a>, Result<&'a ResolvedAssignment<'a>, Error<'a
         &mut self,
         visitor: &'a Assignment<'a>,
     ) -> Result<&'a ResolvedAssignment<'a>, Error<'a>> {
-        Ok(self.bump.alloc(match visitor {
-            Assignment::VariableAssignment { name, expression } => match self.get_variable(name) {
-                Some(x) => {
-                    let expr = expression.accept(self)?;
-                    if expr.data_type() != x.data_type && !expr.data_type().can_convert(x.data_type)
-                    {
-                        return Err(Error::CannotAssignVariable {
-                            assignment: expression,
-                            name: name,
-                            variable: x,
-                        });
-                    }
-                    ResolvedAssignment::StackAssignment {
-                        variable: x,
-                        expression: expr,
-                    }
-                }
-                None => return Err(Error::UnknownVariable { name: name }),
-            },
-            Assignment::PtrAssignment { value, address } => {
-                let resolved_address = address.accept(self)?;
-                let resolved_value = value.accept(self)?;
-                match resolved_address.data_type() {
-                    DataType::PTR(base) => {
-                        if *base != resolved_value.data_type()
-                            && !resolved_value.data_type().can_convert(*base)
-                        {
-                            return Err(Error::CannotAssign {
-                                from: value,
-                                to: address,
-                            });
-                        }
-                        ResolvedAssignment::PtrAssignment {
-                            value: resolved_value,
-                            address: resolved_value,
-                            data_type: *base,
-                        }
-                    }
-                    _ => return Err(Error::DerefOfNonPointer { expr: address }),
-                }
-            }
-            Assignment::ArrayAssignment {
-                index,
-                value,
-                address,
-            } => {
-                let resolved_index = index.accept(self)?;
-                if !resolved_index.data_type().is_number() {
-                    return Err(Error::ArrayIndexNotANumber { index: index });
-                }
-                let resolved_value = value.accept(self)?;
-                let resolved_address = address.accept(self)?;
-                match resolved_address.data_type() {
-                    DataType::PTR(base) => {
-                        if *base != resolved_value.data_type()
-                            && !resolved_value.data_type().can_convert(*base)
-                        {
-                            return Err(Error::CannotAssign {
-                                from: value,
-                                to: address,
-                            });
-                        }
-                        ResolvedAssignment::ArrayAssignment {
-                            index: resolved_index,
-                            value: resolved_value,
-                            address: resolved_address,
-                            data_type: *base,
-                        }
-                    }
-                    _ => return Err(Error::DerefOfNonPointer { expr: address }),
-                }
+        let target = visitor.target.accept(self)?;
+        if !target.is_lvalue() {
+            return Err(Error::NotAnLvalue {
+                location: visitor.location,
+            });
+        }
+        if target.is_const() {
+            return Err(Error::AssignToConst {
+                location: visitor.location,
+            });
+        }
+        let mut value = match visitor.operation {
+            AssignOperation::Assign => self.initializer(visitor.value, target.data_type())?,
+            _ => visitor.value.accept(self)?,
+        };
+        if !Self::can_assign(target.data_type(), value.data_type(), visitor.operation) {
+            return Err(Error::CannotAssign {
+                from: visitor.value,
+                to: visitor.target,
+            });
+        }
+        // the operation is calculated in the common type of both sides and
+        // converted back when it is stored
+        match (visitor.operation, target.data_type()) {
+            (AssignOperation::Compound(BinaryOps::SHL | BinaryOps::SHR), _) => {
+                value = self.promote(value)
             }
-            Assignment::FieldAssignment {
-                name,
-                value,
-                address,
-            } => {
-                let resolved_address = address.accept(self)?;
-                let resolved_value = value.accept(self)?;
-                match resolved_address.data_type() {
-                    DataType::Struct(x) => match x.field(name) {
-                        Some((field_offset, field_type)) => {
-                            if field_type != resolved_value.data_type()
-                                && !resolved_value.data_type().can_convert(field_type)
-                            {
-                                return Err(Error::CannotAssign {
-                                    from: value,
-                                    to: address,
-                                });
-                            }
-                            ResolvedAssignment::FieldAssignment {
-                                field_offset: field_offset,
-                                value: resolved_value,
-                                address: resolved_address,
-                                data_type: field_type,
-                            }
-                        }
-                        None => {
-                            return Err(Error::UnknownField {
-                                expression: address,
-                                name: name,
-                            })
-                        }
-                    },
-                    DataType::PTR(x) => match x {
-                        DataType::Struct(x) => match x.field(name) {
-                            Some((field_offset, field_type)) => {
-                                if field_type != resolved_value.data_type()
-                                    && !resolved_value.data_type().can_convert(field_type)
-                                {
-                                    return Err(Error::CannotAssign {
-                                        from: value,
-                                        to: address,
-                                    });
-                                }
-                                ResolvedAssignment::FieldAssignment {
-                                    field_offset: field_offset,
-                                    value: resolved_value,
-                                    address: resolved_address,
-                                    data_type: field_type,
-                                }
-                            }
-                            None => {
-                                return Err(Error::UnknownField {
-                                    expression: address,
-                                    name: name,
-                                })
-                            }
-                        },
-                        _ => {
-                            return Err(Error::AccessNonStruct {
-                                expression: &address,
-                            })
-                        }
-                    },
-                    _ => {
-                        return Err(Error::AccessNonStruct {
-                            expression: &address,
-                        })
-                    }
-                }
+            (
+                AssignOperation::Compound(BinaryOps::ADD | BinaryOps::SUB)
+                | AssignOperation::Postfix(_),
+                DataType::PTR(element),
+            ) => value = self.scale(value, *element)?,
+            (AssignOperation::Compound(_) | AssignOperation::Postfix(_), data_type)
+                if data_type.is_number() =>
+            {
+                value = self.convert(value, data_type.common(value.data_type()))
             }
+            _ => {}
+        }
+        Ok(self.alloc(ResolvedAssignment {
+            target,
+            value,
+            operation: visitor.operation,
         }))
     }
 }
@@ -865,30 +1602,32 @@ impl<'a> Visitor<&StructExpression<'a>, Result<&'a ResolvedStructExpression<'a>,
         &mut self,
         visitor: &StructExpression<'a>,
     ) -> Result<&'a ResolvedStructExpression<'a>, Error<'a>> {
-        let mut fields = Vec::new();
+        let mut values = Vec::new();
         let mut named_fields = Vec::new();
-        for (name, type_) in visitor.fields.iter().rev() {
-            let type_ = type_.accept(self)?;
-            let data_type = type_.data_type();
-            self.stack_offset += data_type.size();
-            named_fields.push((*name, data_type));
-            let assignment = ResolvedAssignment::StackAssignment {
-                variable: Variable {
-                    stack_offset: self.stack_offset,
-                    data_type,
-                },
-                expression: type_,
+        for (name, value) in &visitor.fields {
+            let value = value.accept(self)?;
+            named_fields.push((*name, value.data_type()));
+            values.push((*name, value));
+        }
+        let struct_ = &*self.alloc(Struct::new(named_fields));
+        self.stack_offset += struct_.size();
+        let stack_offset = self.stack_offset;
+
+        let mut fields = Vec::new();
+        for (name, value) in values {
+            let (offset, data_type) = struct_.field(name).unwrap();
+            let variable = Variable::new(stack_offset - offset, data_type);
+            let assignment = ResolvedAssignment {
+                target: self.alloc(ResolvedExpression::NamedVariable { variable }),
+                value,
+                operation: AssignOperation::Assign,
             };
             fields.push(&*self.alloc(assignment))
         }
-        named_fields.reverse();
-        let data_type = Struct::new(named_fields);
-        let data_type = DataType::Struct(self.alloc(data_type));
-
         Ok(self.alloc(ResolvedStructExpression {
             fields,
-            data_type,
-            stack_offset: self.stack_offset,
+            data_type: DataType::Struct(struct_),
+            stack_offset,
         }))
     }
 }
@@ -913,12 +1652,11 @@ impl<'a> Visitor<&ArrayExpression<'a>, Result<&'a ResolvedArrayExpression<'a>, E
                     if expr.data_type() != first_expr.data_type() {
                         return Err(Error::ArrayOfDifferentTypes {});
                     }
-                    let assignment = ResolvedAssignment::StackAssignment {
-                        variable: Variable {
-                            stack_offset: self.stack_offset,
-                            data_type: expr.data_type(),
-                        },
-                        expression: expr,
+                    let variable = Variable::new(self.stack_offset, expr.data_type());
+                    let assignment = ResolvedAssignment {
+                        target: self.alloc(ResolvedExpression::NamedVariable { variable }),
+                        value: expr,
+                        operation: AssignOperation::Assign,
                     };
                     resolved_expressions.push(&*self.alloc(assignment));
                 }
@@ -958,7 +1696,7 @@ impl<'a> Visitor<&FunctionCall<'a>, Result<&'a ResolvedFunctionCall<'a>, Error<'
                     visitor.parameter.iter().zip(&func.parameter)
                 {
                     let expected = expected.accept(self)?;
-                    let found = found.accept(self)?;
+                    let found = self.initializer(found, expected)?;
                     if expected != found.data_type() && !found.data_type().can_convert(expected) {
                         return Err(Error::ParameterTypeMismatch {
                             function: visitor.name,
@@ -10,6 +10,9 @@ pub mod resolved_compound;
 pub mod resolved_if;
 pub mod resolved_for;
 pub mod resolved_while;
+pub mod resolved_switch;
+pub mod resolved_do_while;
+pub mod resolved_global;
 pub mod resolved_program;
 pub mod resolved_function;
 
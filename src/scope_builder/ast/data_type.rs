@@ -1,39 +1,127 @@
+use std::{cell::OnceCell, cmp::Ordering, fmt};
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum DataType<'a> {
     LONG,
     INT,
+    SHORT,
     CHAR,
+    ULONG,
+    UINT,
+    USHORT,
+    UCHAR,
+    /// `_Bool` only holds 0 or 1
+    BOOL,
     VOID,
     PTR(&'a DataType<'a>),
     Struct(&'a Struct<'a>),
-    EmptyStruct,
+    /// a length of 0 is an array whose length is not known yet
+    Array(&'a DataType<'a>, usize),
 }
 
-#[derive(Debug, PartialEq)]
+/// a struct is incomplete until its fields are known, so `struct Node`
+/// can point to itself. Each definition is a type of its own.
 pub struct Struct<'a> {
-    pub(crate) fields: Vec<(&'a str, DataType<'a>)>,
+    pub(crate) name: &'a str,
+    fields: OnceCell<Vec<(&'a str, DataType<'a>)>>,
+    // all fields of a union start at offset 0
+    pub(crate) is_union: bool,
 }
 
 impl<'a> DataType<'a> {
     pub fn size(&self) -> usize {
         match self {
-            DataType::INT => 4,
-            DataType::CHAR => 1,
-            DataType::LONG => 8,
+            DataType::LONG | DataType::ULONG => 8,
+            DataType::INT | DataType::UINT => 4,
+            DataType::SHORT | DataType::USHORT => 2,
+            DataType::CHAR | DataType::UCHAR | DataType::BOOL => 1,
             DataType::PTR(_) => 8,
-            DataType::Struct(x) => x.fields.iter().map(|x| x.1.size()).sum(),
+            DataType::Struct(x) => x.size(),
+            DataType::Array(element, length) => element.size() * length,
             DataType::VOID => 0,
-            DataType::EmptyStruct => 0,
+        }
+    }
+
+    pub fn alignment(&self) -> usize {
+        match self {
+            DataType::Struct(x) => x.alignment(),
+            DataType::Array(element, _) => element.alignment(),
+            DataType::VOID => 1,
+            x => x.size(),
+        }
+    }
+
+    /// wraps `value` around like a store into this type would
+    pub fn truncate(&self, value: i64) -> i64 {
+        match (self, self.size(), self.is_signed()) {
+            (DataType::BOOL, _, _) => (value != 0) as i64,
+            (_, 1, true) => value as i8 as i64,
+            (_, 1, false) => value as u8 as i64,
+            (_, 2, true) => value as i16 as i64,
+            (_, 2, false) => value as u16 as i64,
+            (_, 4, true) => value as i32 as i64,
+            (_, 4, false) => value as u32 as i64,
+            _ => value,
+        }
+    }
+
+    /// false for structs that are only declared and arrays of them
+    pub fn is_complete(&self) -> bool {
+        match self {
+            DataType::Struct(x) => x.is_complete(),
+            DataType::Array(element, _) => element.is_complete(),
+            _ => true,
         }
     }
 
     pub fn is_number(&self) -> bool {
+        self.is_signed()
+            || matches!(
+                self,
+                DataType::ULONG
+                    | DataType::UINT
+                    | DataType::USHORT
+                    | DataType::UCHAR
+                    | DataType::BOOL
+            )
+    }
+
+    /// decides between the signed and unsigned instructions, pointers are
+    /// compared like unsigned numbers
+    pub fn is_signed(&self) -> bool {
+        matches!(
+            self,
+            DataType::LONG | DataType::INT | DataType::SHORT | DataType::CHAR
+        )
+    }
+
+    /// the type a value of this type is calculated in, everything smaller
+    /// than an int is promoted to int
+    pub fn promote(&self) -> DataType<'a> {
         match self {
-            DataType::INT | DataType::LONG | DataType::CHAR => true,
-            _ => false,
+            x if x.is_number() && x.size() < 4 => DataType::INT,
+            x => *x,
         }
     }
 
+    /// the type both operands of an arithmetic operator are converted to.
+    /// A larger type holds every value of a smaller one, between two of the
+    /// same size the unsigned one wins.
+    pub fn common(&self, other: DataType<'a>) -> DataType<'a> {
+        let (lhs, rhs) = (self.promote(), other.promote());
+        match lhs.size().cmp(&rhs.size()) {
+            Ordering::Greater => lhs,
+            Ordering::Less => rhs,
+            Ordering::Equal if lhs.is_signed() => rhs,
+            Ordering::Equal => lhs,
+        }
+    }
+
+    /// numbers and pointers
+    pub fn is_scalar(&self) -> bool {
+        self.is_number() || matches!(self, DataType::PTR(_))
+    }
+
     pub fn can_convert(&self, other: DataType<'a>) -> bool {
         match (self, other) {
             (DataType::PTR(_), y) if y.is_number() => true,
@@ -48,17 +136,80 @@ impl<'a> DataType<'a> {
 
 impl<'a> Struct<'a> {
     pub fn new(fields: Vec<(&'a str, DataType<'a>)>) -> Struct<'a> {
-        Struct { fields: fields }
+        let struct_ = Struct::declare("struct", false);
+        struct_.define(fields);
+        struct_
     }
 
+    /// an incomplete struct or union, the fields are added by `define`
+    pub fn declare(name: &'a str, is_union: bool) -> Struct<'a> {
+        Struct {
+            name,
+            fields: OnceCell::new(),
+            is_union,
+        }
+    }
+
+    /// completes the struct, false if it was already defined
+    pub fn define(&self, fields: Vec<(&'a str, DataType<'a>)>) -> bool {
+        self.fields.set(fields).is_ok()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.fields.get().is_some()
+    }
+
+    pub fn fields(&self) -> &[(&'a str, DataType<'a>)] {
+        self.fields.get().map_or(&[], |x| x.as_slice())
+    }
+
+    /// like the SysV ABI, the size is rounded up to the alignment so that
+    /// every element of an array is aligned
+    pub fn size(&self) -> usize {
+        let end = match self.is_union {
+            true => self.fields().iter().map(|x| x.1.size()).max().unwrap_or(0),
+            false => self.fields().iter().fold(0usize, |offset, (_, type_)| {
+                offset.next_multiple_of(type_.alignment()) + type_.size()
+            }),
+        };
+        end.next_multiple_of(self.alignment())
+    }
+
+    pub fn alignment(&self) -> usize {
+        self.fields()
+            .iter()
+            .map(|x| x.1.alignment())
+            .max()
+            .unwrap_or(1)
+    }
+
+    /// every field starts at the next multiple of its alignment
     pub fn field(&self, name: &'a str) -> Option<(usize, DataType<'a>)> {
-        let mut offset = 0;
-        for (field_name, type_) in &self.fields {
+        let mut offset: usize = 0;
+        for (field_name, type_) in self.fields() {
+            if !self.is_union {
+                offset = offset.next_multiple_of(type_.alignment());
+            }
             if *field_name == name {
                 return Some((offset, *type_));
             }
-            offset += type_.size()
+            if !self.is_union {
+                offset += type_.size()
+            }
         }
         return None;
     }
 }
+
+impl PartialEq for Struct<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+// the fields are left out, they can refer back to the struct
+impl fmt::Debug for Struct<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
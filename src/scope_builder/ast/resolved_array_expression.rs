@@ -10,7 +10,7 @@ pub enum ResolvedArrayExpression<'a> {
         stack_offset: usize,
     },
     StringLiteral {
-        string: &'a str,
+        string: &'a [u8],
         data_type: DataType<'a>,
         string_label_index: i32
     },
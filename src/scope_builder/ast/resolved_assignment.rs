@@ -1,41 +1,19 @@
-use crate::visitor::Visitable;
+use crate::{parser::ast::AssignOperation, visitor::Visitable};
 
-use super::{resolved_expression::ResolvedExpression, DataType, Variable};
+use super::{resolved_expression::ResolvedExpression, DataType};
 
+/// `target` is an lvalue, see `ResolvedExpression::is_lvalue`
 #[derive(Debug)]
-pub enum ResolvedAssignment<'a> {
-    StackAssignment {
-        variable: Variable<'a>,
-        expression: &'a ResolvedExpression<'a>,
-    },
-    PtrAssignment {
-        data_type: DataType<'a>,
-        value: &'a ResolvedExpression<'a>,
-        address: &'a ResolvedExpression<'a>,
-    },
-    ArrayAssignment {
-        data_type: DataType<'a>,
-        index: &'a ResolvedExpression<'a>,
-        value: &'a ResolvedExpression<'a>,
-        address: &'a ResolvedExpression<'a>,
-    },
-    FieldAssignment {
-        field_offset: usize,
-        data_type: DataType<'a>,
-        value: &'a ResolvedExpression<'a>,
-        address: &'a ResolvedExpression<'a>,
-    },
+pub struct ResolvedAssignment<'a> {
+    pub(crate) target: &'a ResolvedExpression<'a>,
+    pub(crate) value: &'a ResolvedExpression<'a>,
+    pub(crate) operation: AssignOperation,
 }
 
 impl Visitable for ResolvedAssignment<'_> {}
 
 impl<'a> ResolvedAssignment<'a> {
     pub fn data_type(&self) -> DataType<'a> {
-        *match self {
-            ResolvedAssignment::StackAssignment { variable, .. } => &variable.data_type,
-            ResolvedAssignment::PtrAssignment { data_type, .. } => data_type,
-            ResolvedAssignment::ArrayAssignment { data_type, .. } => data_type,
-            ResolvedAssignment::FieldAssignment { data_type, .. } => data_type,
-        }
+        self.target.data_type()
     }
 }
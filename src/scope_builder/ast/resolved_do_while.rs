@@ -0,0 +1,12 @@
+use crate::visitor::Visitable;
+
+use super::{resolved_compound::ResolvedCompound, resolved_expression::ResolvedExpression};
+
+#[derive(Debug)]
+pub struct ResolvedDoWhileStatement<'a> {
+    pub(crate) body: &'a ResolvedCompound<'a>,
+    pub(crate) condition: &'a ResolvedExpression<'a>,
+    pub(crate) label_index: i32,
+}
+
+impl Visitable for ResolvedDoWhileStatement<'_> {}
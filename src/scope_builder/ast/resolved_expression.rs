@@ -6,12 +6,15 @@ use crate::{
 use super::{
     resolved_array_expression::ResolvedArrayExpression, resolved_assignment::ResolvedAssignment,
     resolved_function_call::ResolvedFunctionCall,
-    resolved_struct_expression::ResolvedStructExpression, DataType, Variable,
+    resolved_struct_expression::ResolvedStructExpression, DataType, StorageClass, Variable,
 };
 
 #[derive(Debug)]
 pub enum ResolvedExpression<'a> {
     IntLiteral(i32),
+    UIntLiteral(u32),
+    LongLiteral(i64),
+    ULongLiteral(u64),
     CharLiteral(u8),
     FunctionCall(&'a ResolvedFunctionCall<'a>),
     ArrayExpression(&'a ResolvedArrayExpression<'a>),
@@ -52,6 +55,12 @@ pub enum ResolvedExpression<'a> {
         operation: BinaryOps,
         resulting_type: DataType<'a>,
     },
+    Ternary {
+        condition: &'a ResolvedExpression<'a>,
+        then: &'a ResolvedExpression<'a>,
+        otherwise: &'a ResolvedExpression<'a>,
+        data_type: DataType<'a>,
+    },
 }
 
 impl Visitable for ResolvedExpression<'_> {}
@@ -60,13 +69,16 @@ impl<'a> ResolvedExpression<'a> {
     pub fn data_type(&self) -> DataType<'a> {
         match self {
             ResolvedExpression::IntLiteral(_) => DataType::INT,
+            ResolvedExpression::UIntLiteral(_) => DataType::UINT,
+            ResolvedExpression::LongLiteral(_) => DataType::LONG,
+            ResolvedExpression::ULongLiteral(_) => DataType::ULONG,
             ResolvedExpression::CharLiteral(_) => DataType::CHAR,
             ResolvedExpression::FunctionCall(x) => x.return_type,
             ResolvedExpression::ArrayExpression(a) => a.data_type(),
             ResolvedExpression::StructExpresion(s) => s.data_type,
             ResolvedExpression::Assignment(a) => a.data_type(),
             ResolvedExpression::TypeExpression(t) => *t,
-            ResolvedExpression::SizeOf(_) => DataType::INT,
+            ResolvedExpression::SizeOf(_) => DataType::ULONG,
             ResolvedExpression::FieldAccess { data_type, .. } => *data_type,
             ResolvedExpression::ArrowAccess { data_type, .. } => *data_type,
             ResolvedExpression::Indexing { data_type, .. } => *data_type,
@@ -74,6 +86,179 @@ impl<'a> ResolvedExpression<'a> {
             ResolvedExpression::Unary { resulting_type, .. } => *resulting_type,
             ResolvedExpression::Cast { data_type, .. } => *data_type,
             ResolvedExpression::BinaryExpression { resulting_type, .. } => *resulting_type,
+            ResolvedExpression::Ternary { data_type, .. } => *data_type,
+        }
+    }
+
+    /// the value of an integer constant expression, None if it is only
+    /// known at runtime. Every step wraps around like the generated code.
+    pub fn constant_value(&self) -> Option<i64> {
+        let value = match self {
+            ResolvedExpression::IntLiteral(x) => *x as i64,
+            ResolvedExpression::UIntLiteral(x) => *x as i64,
+            ResolvedExpression::LongLiteral(x) => *x,
+            ResolvedExpression::ULongLiteral(x) => *x as i64,
+            ResolvedExpression::CharLiteral(x) => *x as i8 as i64,
+            ResolvedExpression::SizeOf(x) => *x as i64,
+            ResolvedExpression::Cast { expression, .. } => expression.constant_value()?,
+            // `&((T*)0)->field` is how offsetof is written
+            ResolvedExpression::Unary {
+                expression,
+                operation: UnaryOps::REF,
+                ..
+            } => expression.constant_address()?,
+            ResolvedExpression::Unary {
+                expression,
+                operation,
+                ..
+            } => operation.evaluate(expression.constant_value()?)?,
+            ResolvedExpression::BinaryExpression {
+                lhs,
+                rhs,
+                operation,
+                ..
+            } => {
+                let signed = lhs.data_type().is_signed();
+                let lhs = lhs.constant_value()?;
+                match operation {
+                    BinaryOps::AND if lhs == 0 => 0,
+                    BinaryOps::OR if lhs != 0 => 1,
+                    // the comma operator is not allowed in constants
+                    BinaryOps::COMMA => return None,
+                    _ if signed => operation.evaluate(lhs, rhs.constant_value()?)?,
+                    _ => operation.evaluate_unsigned(lhs, rhs.constant_value()?)?,
+                }
+            }
+            ResolvedExpression::Ternary {
+                condition,
+                then,
+                otherwise,
+                ..
+            } => match condition.constant_value()? {
+                0 => otherwise.constant_value()?,
+                _ => then.constant_value()?,
+            },
+            _ => return None,
+        };
+        Some(self.data_type().truncate(value))
+    }
+
+    /// the value of a pointer that is known when the program is linked, the
+    /// label of a global or static variable and an offset in bytes
+    pub fn static_pointer(&self) -> Option<(&'a str, i64)> {
+        match self {
+            ResolvedExpression::Unary {
+                expression,
+                operation: UnaryOps::REF,
+                ..
+            } => expression.static_location(),
+            // an array decays to the address of its first element
+            ResolvedExpression::Cast { expression, .. } => match expression.data_type() {
+                DataType::Array(..) => expression.static_location(),
+                DataType::PTR(_) => expression.static_pointer(),
+                _ => None,
+            },
+            // the integer side is already scaled by the element size
+            ResolvedExpression::BinaryExpression {
+                lhs,
+                rhs,
+                operation,
+                ..
+            } => match (operation, lhs.static_pointer(), rhs.static_pointer()) {
+                (BinaryOps::ADD, Some((label, offset)), None) => {
+                    Some((label, offset + rhs.constant_value()?))
+                }
+                (BinaryOps::ADD, None, Some((label, offset))) => {
+                    Some((label, offset + lhs.constant_value()?))
+                }
+                (BinaryOps::SUB, Some((label, offset)), None) => {
+                    Some((label, offset - rhs.constant_value()?))
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// the label and offset of a global or static lvalue
+    fn static_location(&self) -> Option<(&'a str, i64)> {
+        match self {
+            ResolvedExpression::NamedVariable { variable } => match variable.storage {
+                StorageClass::Static(label) => Some((label, 0)),
+                StorageClass::Auto => None,
+            },
+            ResolvedExpression::FieldAccess {
+                field_offset,
+                operand,
+                ..
+            } => {
+                let (label, offset) = operand.static_location()?;
+                Some((label, offset + *field_offset as i64))
+            }
+            ResolvedExpression::Indexing {
+                index,
+                operand,
+                data_type,
+            } => {
+                let (label, offset) = operand.static_pointer()?;
+                let index = index.constant_value()?;
+                Some((label, offset + index * data_type.size() as i64))
+            }
+            ResolvedExpression::Unary {
+                expression,
+                operation: UnaryOps::DEREF,
+                ..
+            } => expression.static_pointer(),
+            _ => None,
+        }
+    }
+
+    /// the address of an lvalue if it is known without running the program
+    fn constant_address(&self) -> Option<i64> {
+        match self {
+            ResolvedExpression::ArrowAccess {
+                field_offset,
+                operand,
+                ..
+            } => Some(operand.constant_value()? + *field_offset as i64),
+            ResolvedExpression::FieldAccess {
+                field_offset,
+                operand,
+                ..
+            } => Some(operand.constant_address()? + *field_offset as i64),
+            ResolvedExpression::Unary {
+                expression,
+                operation: UnaryOps::DEREF,
+                ..
+            } => expression.constant_value(),
+            _ => None,
+        }
+    }
+
+    /// true if the expression names an object in memory that can be
+    /// assigned to and whose address can be taken
+    pub fn is_lvalue(&self) -> bool {
+        match self {
+            ResolvedExpression::NamedVariable { .. }
+            | ResolvedExpression::Indexing { .. }
+            | ResolvedExpression::ArrowAccess { .. } => true,
+            ResolvedExpression::Unary { operation, .. } => matches!(operation, UnaryOps::DEREF),
+            ResolvedExpression::FieldAccess { operand, .. } => operand.is_lvalue(),
+            _ => false,
+        }
+    }
+
+    /// true if the lvalue is (part of) a variable declared const
+    pub fn is_const(&self) -> bool {
+        match self {
+            ResolvedExpression::NamedVariable { variable } => variable.is_const,
+            ResolvedExpression::FieldAccess { operand, .. } => operand.is_const(),
+            // the element of an array that decays to a pointer
+            ResolvedExpression::Indexing {
+                operand: ResolvedExpression::Cast { expression, .. },
+                ..
+            } => matches!(expression.data_type(), DataType::Array(..)) && expression.is_const(),
+            _ => false,
         }
     }
 }
@@ -0,0 +1,44 @@
+use crate::visitor::Visitable;
+
+use super::DataType;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Section {
+    Data,
+    Bss,
+    Rodata,
+    /// const, but the linker still fills in addresses
+    DataRelRo,
+}
+
+/// a value that is known before the program runs
+#[derive(Debug)]
+pub enum StaticValue<'a> {
+    Integer {
+        value: i64,
+        size: usize,
+    },
+    /// the address of a string literal
+    String {
+        string: &'a [u8],
+        label_index: i32,
+    },
+    /// the address of a global or static variable plus `offset` bytes
+    Address {
+        label: &'a str,
+        offset: i64,
+    },
+}
+
+/// a global or static variable, `values` holds its initialiser with the
+/// offset of every value. The bytes in between are zero.
+#[derive(Debug)]
+pub struct ResolvedGlobal<'a> {
+    pub(crate) label: &'a str,
+    pub(crate) data_type: DataType<'a>,
+    pub(crate) exported: bool,
+    pub(crate) section: Section,
+    pub(crate) values: Vec<(usize, StaticValue<'a>)>,
+}
+
+impl Visitable for ResolvedGlobal<'_> {}
@@ -1,11 +1,12 @@
 use crate::visitor::Visitable;
 
-use super::resolved_function::ResolvedFunction;
+use super::{resolved_function::ResolvedFunction, resolved_global::ResolvedGlobal};
 
 
 #[derive(Debug)]
 pub struct ResolvedProgram<'a> {
-    pub(crate) functions: Vec<&'a ResolvedFunction<'a>>
+    pub(crate) functions: Vec<&'a ResolvedFunction<'a>>,
+    pub(crate) globals: Vec<&'a ResolvedGlobal<'a>>,
 }
 
 impl Visitable for ResolvedProgram<'_> {}
@@ -4,6 +4,7 @@ use super::{
     resolved_compound::ResolvedCompound, resolved_for::ResolvedForStatement,
     resolved_if::ResolvedIfStatement, resolved_while::ResolvedWhileStatement,
     resolved_expression::ResolvedExpression, resolved_assignment::ResolvedAssignment,
+    resolved_switch::ResolvedSwitchStatement, resolved_do_while::ResolvedDoWhileStatement,
 };
 
 #[derive(Debug)]
@@ -14,11 +15,16 @@ pub enum ResolvedStatement<'a> {
     IfStatement(&'a ResolvedIfStatement<'a>),
     ForStatement(&'a ResolvedForStatement<'a>),
     WhileStatement(&'a ResolvedWhileStatement<'a>),
+    SwitchStatement(&'a ResolvedSwitchStatement<'a>),
+    DoWhileStatement(&'a ResolvedDoWhileStatement<'a>),
+    /// a `case` or `default` label of the enclosing switch
+    Case(i32),
+    Label(i32),
+    Goto(i32),
     VariableDeclaration {
         stack_offset: usize,
         assignment: Option<&'a ResolvedAssignment<'a>>,
     },
-    //TODO: have to select the right label index
     Continue(i32),
     Break(i32),
     Empty,
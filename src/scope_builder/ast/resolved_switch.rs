@@ -0,0 +1,15 @@
+use crate::visitor::Visitable;
+
+use super::{resolved_compound::ResolvedCompound, resolved_expression::ResolvedExpression};
+
+#[derive(Debug)]
+pub struct ResolvedSwitchStatement<'a> {
+    pub(crate) condition: &'a ResolvedExpression<'a>,
+    pub(crate) body: &'a ResolvedCompound<'a>,
+    // the value of every case and the index of its label
+    pub(crate) cases: Vec<(i64, i32)>,
+    pub(crate) default: Option<i32>,
+    pub(crate) label_index: i32,
+}
+
+impl Visitable for ResolvedSwitchStatement<'_> {}
@@ -1,9 +1,21 @@
 use super::DataType;
 
+/// where a variable lives while the program runs
+#[derive(Debug, Clone, Copy)]
+pub enum StorageClass<'a> {
+    /// on the stack, `stack_offset` bytes below %rbp
+    Auto,
+    /// in .data, .bss or .rodata behind the label
+    Static(&'a str),
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct Variable<'a> {
     pub(crate) stack_offset: usize,
     pub(crate) data_type: DataType<'a>,
+    pub(crate) storage: StorageClass<'a>,
+    /// set once a const variable is initialised, it cannot change after
+    pub(crate) is_const: bool,
 }
 
 impl<'a> Variable<'a> {
@@ -11,6 +23,17 @@ impl<'a> Variable<'a> {
         Variable {
             stack_offset,
             data_type,
+            storage: StorageClass::Auto,
+            is_const: false,
+        }
+    }
+
+    pub fn at_label(label: &'a str, data_type: DataType<'a>) -> Variable<'a> {
+        Variable {
+            stack_offset: 0,
+            data_type,
+            storage: StorageClass::Static(label),
+            is_const: false,
         }
     }
 }
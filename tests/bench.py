@@ -0,0 +1,83 @@
+#!/usr/bin/env python3
+"""Times the compiler on generated sources of growing size.
+
+usage: python3 tests/bench.py [--lex] COMPILER [BASELINE]
+
+With a second compiler both are timed on the same files and the speedup of
+the first over the baseline is printed. With --lex only the lexer is timed,
+as the compiler reports it for `-lex`, instead of the whole run.
+"""
+
+import os
+import re
+import subprocess
+import sys
+import tempfile
+import time
+
+SIZES = [250, 500, 1000, 2000, 4000]
+RUNS = 3
+
+FUNCTION = """int function{0}(int a, int b) {{
+    int c = a + b * {0};
+    while (c > 0) {{
+        c = c - 1;
+    }}
+    if (a == b) {{
+        return c;
+    }}
+    return a - b;
+}}
+"""
+
+
+def generate(path, functions):
+    with open(path, "w") as file:
+        for i in range(functions):
+            file.write(FUNCTION.format(i))
+        file.write("int main() {\n    return function0(1, 2);\n}\n")
+
+
+def measure(compiler, source, output, lex):
+    best = None
+    for _ in range(RUNS):
+        command = [compiler, source, output] + (["-lex"] if lex else [])
+        start = time.perf_counter()
+        result = subprocess.run(command, capture_output=True, text=True)
+        elapsed = time.perf_counter() - start
+        if result.returncode != 0:
+            sys.exit(f"{compiler} failed on {source}")
+        if lex:
+            match = re.search(r"lexed in ([\d.]+)s", result.stdout)
+            if match is None:
+                sys.exit(f"{compiler} does not support -lex")
+            elapsed = float(match.group(1))
+        best = elapsed if best is None else min(best, elapsed)
+    return best
+
+
+def main():
+    arguments = sys.argv[1:]
+    lex = "--lex" in arguments
+    compilers = [x for x in arguments if x != "--lex"][:2]
+    if not compilers:
+        sys.exit(__doc__)
+
+    with tempfile.TemporaryDirectory() as directory:
+        source = os.path.join(directory, "bench.c")
+        output = os.path.join(directory, "bench.s")
+        print(f"{'lines':>8}" + "".join(f"{os.path.basename(x):>16}" for x in compilers)
+              + ("  speedup" if len(compilers) == 2 else ""))
+        for functions in SIZES:
+            generate(source, functions)
+            with open(source) as file:
+                lines = sum(1 for _ in file)
+            times = [measure(x, source, output, lex) for x in compilers]
+            row = f"{lines:>8}" + "".join(f"{x:>15.4f}s" for x in times)
+            if len(times) == 2:
+                row += f"  {times[1] / times[0]:>6.1f}x"
+            print(row)
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,36 @@
+#include <stdio.h>
+
+// the pointer can change, only the chars it points to are const
+static const char* name = "first";
+const char* const fixed = "fixed";
+const int limit = 3;
+int const also = 4;
+const int primes[3] = {2, 3, 5};
+
+struct Point {
+    int x;
+    int y;
+};
+const struct Point origin = {.x = 1, .y = 2};
+
+int main() {
+    puts(name);
+    name = "second";
+    puts(name);
+    name++;
+    puts(name);
+    puts(fixed);
+    putchar('0' + limit + also + primes[2]);
+    putchar('\n');
+
+    const char* local = name;
+    local = fixed;
+    puts(local);
+    char buffer[3] = "ab";
+    char* const cursor = buffer;
+    *cursor = 'x';
+    puts(cursor);
+    putchar('0' + origin.x + origin.y);
+    putchar('\n');
+    return 0;
+}
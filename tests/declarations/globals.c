@@ -0,0 +1,108 @@
+#include <stdio.h>
+
+typedef struct Point {
+    int x;
+    int y;
+} Point;
+
+int counter;
+long big = 5000000000;
+char letter = 'g';
+static int hidden = -3;
+const int answer = 42;
+char* greeting = "hi";
+Point origin = {.y = 2, .x = 1};
+Point zero;
+int* nothing = 0;
+
+// addresses of globals are constants too
+int* address = &counter;
+int primes[4] = {2, 3, 5, 7};
+int* first = primes;
+int* third = &primes[2];
+int* last = primes + 3;
+int* before = &primes[3] - 2;
+int* y = &origin.y;
+Point* pointer = &origin;
+char* byte = (char*)&big + 1;
+Point* points[2] = {&origin, &zero};
+static int* hidden_address = &hidden;
+
+// every call sees the value the last one left
+int next() {
+    static int calls = 10;
+    static int* self = &calls;
+    *self += 0;
+    calls++;
+    return calls;
+}
+
+int other() {
+    // not the same variable as the one in next
+    static int calls;
+    calls += 2;
+    return calls;
+}
+
+void count() {
+    counter++;
+}
+
+int shadow() {
+    int counter = 7;
+    return counter;
+}
+
+int main() {
+    count();
+    count();
+    putchar('0' + counter);
+    putchar('0' + big / 1000000000);
+    putchar(letter);
+    putchar('0' - hidden);
+    putchar('0' + answer / 10);
+    putchar('\n');
+
+    putchar(greeting[0]);
+    putchar(greeting[1]);
+    greeting = "yo";
+    putchar(greeting[0]);
+    putchar('\n');
+
+    putchar('0' + origin.x);
+    putchar('0' + origin.y);
+    origin.x = 5;
+    Point* p = &origin;
+    p->y += 3;
+    putchar('0' + origin.x);
+    putchar('0' + origin.y);
+    putchar('0' + zero.x + zero.y);
+    zero = origin;
+    putchar('0' + zero.x);
+    putchar('0' + (nothing == 0));
+    putchar('\n');
+
+    putchar('0' + next() % 10);
+    putchar('0' + next() % 10);
+    putchar('0' + other());
+    putchar('0' + other());
+    putchar('0' + shadow());
+    putchar('0' + counter);
+    putchar('\n');
+
+    putchar('0' + *address);
+    putchar('0' + *first + *third + *last);
+    putchar('0' + *before);
+    putchar('0' + *y);
+    putchar('0' + pointer->x);
+    putchar('0' + (*byte == (char)(big >> 8)));
+    putchar('0' + points[1]->x);
+    putchar('0' - *hidden_address);
+    *address = 4;
+    putchar('0' + counter);
+    putchar('\n');
+
+    int* c = &counter;
+    *c = 9;
+    return counter + hidden;
+}
@@ -0,0 +1,7 @@
+// const variables can only be initialised
+const int limit = 3;
+
+int main() {
+    limit = 4; // error: AssignToConst 5
+    return limit;
+}
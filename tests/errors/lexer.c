@@ -0,0 +1,8 @@
+// every lex error of the file is reported, not only the first one
+int main() {
+    int a = 1 @ 2; // error: UnknownCharacter 15
+    char* s = "open; // error: UnterminatedString 15
+    char c = 'x; // error: UnterminatedCharLiteral 14
+    int b = a $ 3; // error: UnknownCharacter 15
+    return a + b;
+}
@@ -0,0 +1,12 @@
+// errors after an include and a macro call over several lines are still
+// reported at their place in this file
+#include "../preprocessor/guarded.h"
+
+#define TWICE(x) \
+    ((x) * 2)
+
+int main() {
+    int value = TWICE(
+        3);
+    return value value; // error: UnexpectedToken 18
+}
@@ -0,0 +1,94 @@
+#include <stdio.h>
+
+typedef struct Point {
+    int x;
+    int y;
+} Point;
+
+// print a letter, so the output shows how often they were called
+Point* get(Point* p) {
+    putchar('g');
+    return p;
+}
+
+int index(int i) {
+    putchar('i');
+    return i;
+}
+
+int main() {
+    int a = 10;
+    a += 5;
+    a -= 3;
+    a *= 4;
+    a /= 6;
+    a %= 5;
+    putchar('0' + (a == 3));
+
+    int bits = 0x0f;
+    bits &= 0x3c;
+    bits |= 0x40;
+    bits ^= 0x05;
+    bits <<= 2;
+    bits >>= 1;
+    putchar('0' + (bits == 0x92));
+
+    // the value of an assignment is the new value
+    int b = 1;
+    int c = (b += 2) * 10;
+    putchar('0' + (b == 3 && c == 30));
+    char small = 'a';
+    small += 2;
+    putchar(small);
+    putchar('\n');
+
+    // prefix and postfix increments
+    int i = 5;
+    int pre = ++i;
+    int post = i++;
+    putchar('0' + (pre == 6 && post == 6 && i == 7));
+    int d = i--;
+    int e = --i;
+    putchar('0' + (d == 7 && e == 5 && i == 5));
+    int sum = 0;
+    int k;
+    for (k = 0; k < 5; k++) {
+        sum += k;
+    }
+    putchar('0' + (sum == 10));
+    int n = 3;
+    int f = n++ + n++;
+    putchar('0' + (f == 7 && n == 5));
+    putchar('\n');
+
+    // through pointers, arrays and fields
+    int value = 1;
+    int* p = &value;
+    *p += 4;
+    ++*p;
+    (*p)++;
+    putchar('0' + value);
+
+    int array[] = {1, 2, 3};
+    array[1] *= 3;
+    array[2]++;
+    --array[0];
+    putchar('0' + array[0] + array[1] + array[2]);
+
+    Point point = {.x = 1, .y = 2};
+    point.x += 3;
+    point.y++;
+    putchar('0' + point.x + point.y);
+    putchar('\n');
+
+    // the address of the left side is only computed once
+    get(&point)->x += point.y - 1;
+    putchar('0' + point.x);
+    array[index(1)]++;
+    putchar('0' + array[1]);
+    get(&point)->y--;
+    putchar('0' + point.y);
+    putchar('\n');
+
+    return a + bits;
+}
@@ -0,0 +1,68 @@
+#include <stdio.h>
+
+#define KB (1 << 10)
+#if KB / 4 == 256 && (KB > 1000 ? 1 : 0)
+#define SIZE 2
+#else
+#define SIZE 0
+#endif
+
+enum Flags {
+    READ = 1 << 0,
+    WRITE = 1 << 1,
+    EXEC = 1 << 2,
+    ALL = READ | WRITE | EXEC,
+    MASK = ~ALL & 0xff,
+    HALF = ALL / 2 + (ALL % 2 == 1),
+    HEADER = sizeof(long) * 3,
+};
+
+_Static_assert(ALL == 7, "all flags");
+_Static_assert(sizeof(long) == 8);
+
+int limit = KB * SIZE - 1;
+long huge = 1L << 40;
+char wrapped = (char)300;
+int negative = -(3 * 4) >> 1;
+int picked = SIZE > 1 ? 'y' : 'n';
+
+char describe(int flags) {
+    switch (flags) {
+        case READ:
+            return 'r';
+        case READ | WRITE:
+            return 'w';
+        case ALL:
+            return 'a';
+        case ALL + 1:
+            return '+';
+        case -ALL:
+            return '-';
+    }
+    return '?';
+}
+
+int main() {
+    _Static_assert(MASK == 248, "mask");
+    putchar('0' + ALL);
+    putchar('0' + HALF);
+    putchar('0' + HEADER / 10);
+    putchar('0' + MASK / 100);
+    putchar('\n');
+
+    putchar('0' + limit / 1000);
+    putchar('0' + huge / 1000000000000);
+    putchar('0' + wrapped);
+    putchar('0' - negative);
+    putchar(picked);
+    putchar('\n');
+
+    putchar(describe(1));
+    putchar(describe(3));
+    putchar(describe(7));
+    putchar(describe(8));
+    putchar(describe(-7));
+    putchar(describe(5));
+    putchar('\n');
+    return 0;
+}
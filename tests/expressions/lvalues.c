@@ -0,0 +1,76 @@
+#include <stdio.h>
+
+typedef struct Point {
+    int x;
+    int y;
+} Point;
+
+typedef struct Line {
+    Point from;
+    Point to;
+} Line;
+
+typedef struct Shape {
+    Point* points;
+    Line line;
+} Shape;
+
+void set(int* target, int value) {
+    *target = value;
+}
+
+int main() {
+    Point a = {.x = 1, .y = 2};
+    Point b = {.x = 3, .y = 4};
+    Point c = {.x = 5, .y = 6};
+    Point points[] = {a, b, c};
+
+    Shape shape = {.points = points, .line = {.from = a, .to = b}};
+    Shape* ps = &shape;
+    Shape** pps = &ps;
+
+    // nested fields, arrows, indexing and derefs
+    (*pps)->points[1].x = 7;
+    (**pps).points[2].y += 10;
+    shape.line.to.y = 8;
+    ps->line.from.x *= 9;
+    putchar('0' + points[1].x);
+    putchar('0' + points[2].y - 10);
+    putchar('0' + shape.line.to.y);
+    putchar('0' + shape.line.from.x);
+    putchar('\n');
+
+    // a computed address
+    char buffer[] = {'a', 'b', 'c', '\n'};
+    char* p = buffer;
+    *(p + 1) = 'x';
+    *(buffer + 2) += 1;
+    putchar(buffer[0]);
+    putchar(buffer[1]);
+    putchar(buffer[2]);
+    putchar(buffer[3]);
+
+    // taking the address of any lvalue
+    int numbers[] = {1, 2, 3};
+    set(&numbers[1], 5);
+    set(&ps->points[0].y, 4);
+    set(&shape.line.to.x, 6);
+    int* q = &numbers[2];
+    *q = 9;
+    putchar('0' + numbers[1]);
+    putchar('0' + numbers[2]);
+    putchar('0' + points[0].y);
+    putchar('0' + shape.line.to.x);
+    putchar('\n');
+
+    // whole structs through an lvalue
+    Point copy = {.x = 0, .y = 0};
+    copy = points[2];
+    points[0] = copy;
+    ps->line.to = points[1];
+    putchar('0' + points[0].x);
+    putchar('0' + shape.line.to.x);
+    putchar('\n');
+
+    return copy.x + shape.line.to.y;
+}
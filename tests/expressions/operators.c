@@ -0,0 +1,77 @@
+#include <stdio.h>
+
+#define FLAGS (1 << 3 | 1 << 1)
+#if FLAGS & 2 && (FLAGS ^ 10) == 0 && (1 ? 4 : 5) >> 1 == 2
+#define MODE 1
+#else
+#define MODE 0
+#endif
+
+// prints its argument, so the output shows what was evaluated
+int tick(int value) {
+    putchar('0' + value);
+    return value;
+}
+
+int shift(int value, int bits, int back) {
+    // the shift amount goes through %cl, the arguments must survive
+    return (value << bits) >> back;
+}
+
+int main() {
+    int a = 0x5c;
+    int b = 0x33;
+
+    // bitwise operators
+    int and = a & b;
+    int or = a | b;
+    int xor = a ^ b;
+    int mix = a & b | a ^ b & ~a;
+    putchar('0' + (and == 0x10));
+    putchar('0' + (or == 0x7f));
+    putchar('0' + (xor == 0x6f));
+    putchar('0' + (mix == (a & b | (a ^ (b & ~a)))));
+
+    // shifts bind tighter than comparisons and looser than additions
+    int shifted = 1 << 2 + 1;
+    int back = -64 >> 3;
+    long wide = 1L << 40;
+    char small = 3;
+    putchar('0' + (shifted == 8));
+    putchar('0' + (back == -8));
+    putchar('0' + (wide >> 38 == 4));
+    putchar('0' + (small << 2 == 12));
+    putchar('0' + (shift(3, 4, 2) == 12));
+    putchar('0' + (1 << 3 < 9));
+
+    // & binds tighter than ^, ^ tighter than |, | tighter than &&
+    putchar('0' + ((6 & 3 ^ 1 | 8) == 11));
+    putchar('0' + (0 | 1 && 2 & 2));
+
+    // the conditional operator is right associative
+    int x = 5;
+    int sign = x < 0 ? -1 : x > 0 ? 1 : 0;
+    int nested = x ? x > 3 ? 30 : 3 : 0;
+    putchar('0' + (sign == 1));
+    putchar('0' + (nested == 30));
+    putchar('0' + ((0 ? 1 : 2) + 1 == 3));
+
+    // only one side of the conditional is evaluated
+    int picked = x > 2 ? tick(7) : tick(9);
+    putchar('0' + (picked == 7));
+
+    // the comma operator evaluates left to right and keeps the last value
+    int i;
+    int j;
+    int sum = 0;
+    for (i = 0, j = 10; i < j; i = i + 1, j = j - 1) {
+        sum = sum + j - i;
+    }
+    putchar('0' + (sum == 30));
+    int last = (tick(1), tick(2), tick(3));
+    putchar('0' + (last == 3));
+    putchar('0' + MODE);
+    putchar('\n');
+
+    return (a ^ b) & 0x3f;
+}
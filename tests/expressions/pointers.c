@@ -0,0 +1,129 @@
+#include <stdio.h>
+
+typedef struct Point {
+    int x;
+    int y;
+    char tag;
+} Point;
+
+void print(long n) {
+    if (n < 0) {
+        putchar('-');
+        n = -n;
+    }
+    if (n >= 10) {
+        print(n / 10);
+    }
+    putchar('0' + n % 10);
+}
+
+void line(long n) {
+    print(n);
+    putchar('\n');
+}
+
+// walks the array by pointer instead of by index
+int sum(int* begin, int* end) {
+    int total = 0;
+    while (begin < end) {
+        total += *begin++;
+    }
+    return total;
+}
+
+long length(char* text) {
+    char* end = text;
+    while (*end) {
+        end++;
+    }
+    return end - text;
+}
+
+int main() {
+    int values[6] = {1, 2, 3, 4, 5, 6};
+    int* p = values;
+
+    // adding an integer moves by whole elements
+    line(*(p + 2));
+    line(*(3 + p));
+    line(p[1] + *(p + 5));
+    int* q = &values[5];
+    line(*(q - 1));
+    int back = -2;
+    line(*(q + back));
+    unsigned char two = 2;
+    line(*(p + two));
+
+    // the difference counts elements and is a long
+    line(q - p);
+    line(p - q);
+    line(sizeof(q - p));
+    line(&values[4] - &values[1]);
+    line(sum(values, values + 6));
+    line(sum(p + 1, q));
+
+    // compound assignments and increments
+    p += 3;
+    line(*p);
+    p -= 2;
+    line(*p);
+    p++;
+    line(*p);
+    ++p;
+    line(*p);
+    p--;
+    line(*p);
+    line(*--p);
+
+    // relational comparisons
+    line(p < q);
+    line(q < p);
+    line(p <= p);
+    line(q >= p);
+    line(q > values);
+    line(p != q);
+    line(p == &values[1]);
+
+    // larger elements
+    long longs[3] = {10, 20, 30};
+    long* l = longs;
+    line(*(l + 2));
+    line(&longs[2] - l);
+    line((char*)(l + 1) - (char*)l);
+
+    Point points[3];
+    for (int i = 0; i < 3; i++) {
+        points[i].x = i;
+        points[i].y = i * 10;
+    }
+    Point* last = points + 2;
+    line(last->y);
+    line((last - 1)->x);
+    line(last - points);
+    line((char*)last - (char*)points);
+    line((char*)&points[1] - (char*)points == sizeof(Point));
+
+    // pointers to pointers move by the size of a pointer
+    int* table[3] = {&values[0], &values[2], &values[4]};
+    int** t = table;
+    line(**(t + 2));
+    line(*t[1]);
+    line((t + 3) - t);
+
+    char word[] = "pointer";
+    line(length(word));
+    line(length(word + 3));
+    char* c = word + 7;
+    line(c - word);
+    line(*(c - 7) == 'p');
+
+    // void pointers step over single bytes like gcc does
+    void* raw = values;
+    void* next = raw + 4;
+    line((char*)next - (char*)raw);
+    line(*(int*)next);
+
+    _Static_assert((long)((int*)0 + 3) == 12, "scaled constants");
+    _Static_assert((long*)16 - (long*)0 == 2, "constant differences");
+    return 0;
+}
@@ -0,0 +1,139 @@
+#include <stdio.h>
+
+void print(long n) {
+    if (n < 0) {
+        putchar('-');
+        n = -n;
+    }
+    if (n >= 10) {
+        print(n / 10);
+    }
+    putchar('0' + n % 10);
+}
+
+void line(long n) {
+    print(n);
+    putchar('\n');
+}
+
+// the argument is converted to the parameter
+long widen(long value) {
+    return value;
+}
+
+// the return value is converted to the return type
+char narrow(int value) {
+    return value;
+}
+
+int main() {
+    // small integers are calculated as int
+    char c = 100;
+    line(c + c);
+    unsigned char u = 200;
+    line(u + u);
+    line(u * 2 / 4);
+    short s = 30000;
+    line(s + s);
+    line(-u);
+    line(~c);
+    line(sizeof(c + c));
+    line(sizeof(-c));
+    line(sizeof(!c));
+    line(sizeof(c << 1));
+
+    // mixed operands are converted to the larger type
+    int negative = -5;
+    long big = 10000000000;
+    line(big + negative);
+    line(negative * 1000000000L);
+    line(sizeof(negative + big));
+    char minus = -1;
+    line(minus + big);
+
+    // between types of the same size unsigned wins
+    unsigned int one = 1;
+    line(negative < one);
+    line(negative + one > 0);
+    line((unsigned long)negative > 5);
+    line(minus < u);
+
+    // unsigned constants convert the signed side too
+    line(negative < 1U);
+    line(minus < 0U);
+    line(negative < 1UL);
+    line(negative < 1L);
+    line(negative / 2U);
+    line(negative / 2UL);
+    line(big / 3U);
+    line(negative >> 1U);
+    line(negative % 3U);
+    line(negative % 3);
+    line(-16 >> 2U);
+    line(0xFFFFFFFF >> 28);
+    line(0x80000000 / -1);
+    line(minus % 0x7U);
+    line(u * 0x1000000U > 0);
+
+    // sizeof is an unsigned long
+    line(sizeof(sizeof(char)));
+    line(-1 < sizeof(int));
+    line(sizeof(int) - 5 > 0);
+    line(negative / sizeof(int) > 1000);
+    line(negative + sizeof(long));
+    line(sizeof(big) > negative);
+
+    // casts extend by the sign of the value and truncate
+    line((long)negative);
+    line((long)(unsigned int)negative);
+    line((char)300);
+    line((unsigned char)-1);
+    line((short)70000);
+    line((int)big);
+
+    // assignments convert the value
+    long wide = negative;
+    line(wide);
+    int from_char = minus;
+    line(from_char);
+    unsigned int from_negative = minus;
+    line(from_negative);
+    char truncated = 1000;
+    line(truncated);
+    line(widen(negative));
+    line(narrow(321));
+
+    // compound assignments calculate in the common type
+    char total = 100;
+    total += 100;
+    line(total);
+    unsigned char small = 10;
+    small -= 20;
+    line(small);
+    int halved = 100;
+    halved /= 2L;
+    line(halved);
+    short counter = 10;
+    counter *= -3;
+    line(counter);
+    int shifted = 1;
+    shifted <<= 4L;
+    line(shifted);
+
+    // logical operators give 0 or 1
+    line(2 || 0);
+    line(0 || 7);
+    line(3 && 4);
+    line(big && negative);
+    line(!big);
+
+    // the ternary converts both branches
+    line(one ? negative : big);
+    line(sizeof(one ? c : big));
+
+    // negative indices are not zero extended
+    int values[4] = {1, 2, 3, 4};
+    int* last = &values[3];
+    line(last[negative + 3]);
+    return 0;
+}
@@ -0,0 +1,31 @@
+// a comment on the first line
+#include <stdio.h> // after a directive
+
+/*
+ * a block comment
+ * over multiple lines
+ */
+#define VALUE 4 /* inside a definition */
+#define TWICE(x) ((x) /* comment */ * 2)
+
+/* int hidden() { return 1; } */
+
+int add(int a, /* between parameters */ int b) {
+    return a + b; // trailing
+}
+
+int main() {
+    int x = 1; /* block */ int y = 2;
+    // int x = 100;
+    int z = x/**/+y;
+    int w = 8 / /* not a comment start */ 2;
+    int v = 6 // /* the block does not start here
+        ;
+    /* a // line comment inside of a block */
+    puts("/* this is a string */");
+    puts("// and so is this");
+    /**/
+    /***/
+    /* ** / */
+    return add(z, TWICE(VALUE)) + w + v;
+}
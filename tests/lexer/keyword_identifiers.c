@@ -0,0 +1,86 @@
+#include <stdio.h>
+
+#define format_width 3
+#define iffy(x) ((x) + format_width)
+#ifdef iffy
+#define checked 1
+#endif
+#if defined(checked) && !defined undef
+#undef checked
+#elif 1
+#error not reached
+#endif
+
+typedef struct structure {
+    int integer;
+    char character;
+    long longest;
+} structure;
+
+int returned(int interval) {
+    return interval + 1;
+}
+
+void voidness(structure *structural) {
+    structural->integer = structural->integer + 1;
+}
+
+int for_each(int forward, int format) {
+    int whiled = 0;
+    for (int fork = 0; fork < forward; fork = fork + 1) {
+        whiled = whiled + format;
+    }
+    return whiled;
+}
+
+// the names of the directives are not keywords in C code
+int undef(int ifdef) {
+    return ifdef * 2;
+}
+
+int elif(int endif) {
+    return endif - 1;
+}
+
+int main() {
+    int defined = 1;
+    int define = 2;
+    int include = define + 1;
+    int ifndef = undef(defined);
+
+    structure elsewhere;
+    elsewhere.integer = 40;
+    elsewhere.character = 'c';
+    elsewhere.longest = 7;
+    voidness(&elsewhere);
+
+    int breaker = 0;
+    int continued = 0;
+    for (breaker = 1; breaker < 5; breaker = breaker + 1) {
+        if (breaker == 2) {
+            continue;
+        }
+        continued = continued + breaker;
+    }
+    while (1) {
+        if (continued > 10) {
+            break;
+        }
+        continued = continued + 1;
+    }
+
+    int sizeofs = sizeof(long);
+    int typedefs = 2;
+    int defines = 3;
+    int included = 4;
+    int undefined = 5;
+    int elifant = 6;
+    int endiff = 7;
+    int ifdefs = 8;
+    int charm = elsewhere.character;
+
+    putchar(charm);
+    putchar(10);
+    return returned(elsewhere.integer) + for_each(typedefs, defines) + iffy(included) + undefined
+        + elifant + endiff + ifdefs + continued + sizeofs - 80 + elif(ifndef) + include - define;
+}
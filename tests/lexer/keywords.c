@@ -0,0 +1,10 @@
+#include <stdio.h>
+
+int main() {
+    int x = 0;
+    for(int i=0;i<3;i=i+1){x=x+i;}
+    if(x==3){putchar('y');}else{putchar('n');}
+    while(x){x=x-1;}
+    putchar(10);
+    return(x+sizeof(long));
+}
@@ -0,0 +1,29 @@
+#include <stdio.h>
+
+int main() {
+    // integer literals in every base
+    int decimal = 42;
+    int hex = 0x2a + 0X2A + 0xff;
+    int octal = 052 + 0;
+    int binary = 0b101010 + 0B1;
+    long big = 4294967296;
+    long suffixed = 10L + 10l + 10LL + 10u + 10UL + 10ull + 0x10LU;
+    long wide = 0x7fffffffffffffff;
+
+    // escape sequences in char literals
+    char escapes = '\n' + '\t' + '\r' + '\a' + '\b' + '\f' + '\v' + '\0';
+    char quotes = '\'' + '"' + '\"' + '\\' + '\?';
+    char numeric = '\x41' + '\101' + '\7' + '\x7';
+
+    // and in string literals
+    puts("tab\there \"quoted\" back\\slash");
+    puts("octal \101\102\103 hex \x44\x45 bell-free \x3f");
+    puts("single ' quote and \'escaped\' one");
+    putchar(numeric - 'A' - 'A' + '0');
+    putchar('\n');
+
+    if (big != 4294967296L || wide != 9223372036854775807 || suffixed != 0x4c) {
+        return 1;
+    }
+    return (decimal + hex + octal + binary + escapes + quotes) % 256;
+}
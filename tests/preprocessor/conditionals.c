@@ -0,0 +1,108 @@
+#include <stdio.h>
+
+#define LEVEL 3
+#define EMPTY
+#define TWICE(x) ((x) * 2)
+
+void print(int n) {
+    if (n >= 10) {
+        print(n / 10);
+    }
+    putchar('0' + n % 10);
+}
+
+int main() {
+#if LEVEL > 2
+    putchar('a');
+#else
+    putchar('b');
+#endif
+
+#if LEVEL == 1
+    putchar('c');
+#elif LEVEL == 2
+    putchar('d');
+#elif LEVEL == 3
+    putchar('e');
+#else
+    putchar('f');
+#endif
+
+#if 0
+    this is never compiled
+#if 1
+    neither is this
+#else
+    or this
+#endif
+#elif 1
+    putchar('g');
+#endif
+
+    // skipped groups do not have to be valid C
+#if 0
+    don't stop at "unbalanced quotes
+    or characters like @ and $
+#endif
+
+    // `defined` works with and without parentheses
+#if defined LEVEL && defined(EMPTY) && !defined(MISSING)
+    putchar('h');
+#endif
+#ifdef EMPTY
+    putchar('i');
+#endif
+#ifndef MISSING
+    putchar('j');
+#endif
+#ifdef MISSING
+    putchar('k');
+#endif
+    putchar('\n');
+
+    // unknown identifiers are 0, macros are expanded first
+#if MISSING || UNKNOWN_NAME
+    putchar('l');
+#endif
+#if TWICE(LEVEL) == 6
+    putchar('m');
+#endif
+#if (LEVEL << 2 | 1) == 13 && 10 / 3 == 3 && 10 % 3 == 1 && -1 < 0
+    putchar('n');
+#endif
+#if LEVEL > 5 ? 0 : 1
+    putchar('o');
+#endif
+#if 0x10 == 16 && 010 == 8 && 'A' == 65 && ~0 == -1
+    putchar('p');
+#endif
+#if 0 && 1 / 0
+    putchar('q');
+#endif
+#if 1 || 1 / 0
+    putchar('r');
+#endif
+
+    // values are long or unsigned long, the signed side is converted
+#if -1 > 0u && -1 / 2u > 0 && -1u > 0 && ~0u == -1
+    putchar('u');
+#endif
+#if 0xFFFFFFFF > -1 && -1 < 1 && (0 ? 1u : -1) > 0 && !(1u - 2 < 0)
+    putchar('v');
+#endif
+#if -1 >> 63u == -1 && 0xFFFFFFFFFFFFFFFF > 0 && 2147483648 * 2 == 4294967296
+    putchar('w');
+#endif
+    putchar('\n');
+
+#undef LEVEL
+#ifdef LEVEL
+    putchar('s');
+#else
+    putchar('t');
+#endif
+#define LEVEL 7
+    print(LEVEL);
+    putchar('\n');
+    return 0;
+}
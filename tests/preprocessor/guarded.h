@@ -0,0 +1,14 @@
+#ifndef GUARDED_H
+#define GUARDED_H
+
+// a second include would redefine the struct
+typedef struct Pair {
+    int first;
+    int second;
+} Pair;
+
+int sum(Pair pair) {
+    return pair.first + pair.second;
+}
+
+#endif
@@ -0,0 +1,26 @@
+#include <stdio.h>
+#include <stdlib.h>
+#include "guarded.h"
+#include "guarded.h"
+#include "once.h"
+#include "once.h"
+#include <stdio.h>
+
+int main() {
+    Pair pair;
+    pair.first = 2;
+    pair.second = 3;
+    putchar('0' + sum(pair));
+    putchar('0' + doubled(4));
+
+    // the headers in include/ declare the C library
+    int* values = malloc(3 * sizeof(int));
+    values[2] = 7;
+    putchar('0' + values[2]);
+    free(values);
+    putchar('0' + (EOF < 0));
+    putchar('0' + (NULL == 0));
+    putchar('0' + EXIT_FAILURE);
+    putchar('\n');
+    return EXIT_SUCCESS;
+}
@@ -0,0 +1,110 @@
+#include <stdio.h>
+#include <string.h>
+
+#define STR(x) #x
+#define XSTR(x) STR(x)
+#define CAT(a, b) a##b
+#define XCAT(a, b) CAT(a, b)
+#define FIELD(name) int CAT(field_, name)
+
+#define SUM(...) add(3, __VA_ARGS__)
+#define FIRST(first, ...) first
+// without arguments the comma before them is removed
+#define LAST(...) (0, ##__VA_ARGS__)
+#define CALL(f, ...) f(__VA_ARGS__)
+
+#define VERSION 4
+#define NEXT (VERSION + 1)
+// a comment is a space, even if it spans lines
+#define SPREAD(a, b) /* the first
+                        and the second */ ((a) * 10 + (b))
+#define AFTER /* comment */ 3 /*
+                            */ + 4
+
+void print(int n) {
+    if (n < 0) {
+        putchar('-');
+        n = -n;
+    }
+    if (n >= 10) {
+        print(n / 10);
+    }
+    putchar('0' + n % 10);
+}
+
+void line(int n) {
+    print(n);
+    putchar('\n');
+}
+
+int add(int count, int a, int b, int c) {
+    return count * 100 + a + b + c;
+}
+
+int twice(int x) {
+    return x * 2;
+}
+
+typedef struct Fields {
+    FIELD(a);
+    FIELD(b);
+} Fields;
+
+int main() {
+    // `#` turns the argument into a string
+    puts(STR(hello world));
+    puts(STR(a + b * "c"));
+    puts(XSTR(VERSION));
+    puts(STR(VERSION));
+    puts(XSTR(CAT(x, 1)));
+    line(NEXT * 2);
+    line(strlen(STR(  spaced   out  )));
+
+    // `##` pastes two tokens into one
+    int CAT(my, var) = 7;
+    line(myvar);
+    line(CAT(12, 34));
+    line(XCAT(VER, SION));
+    Fields fields;
+    fields.field_a = 1;
+    fields.field_b = 2;
+    line(fields.field_a + fields.field_b);
+
+    // variadic macros
+    line(SUM(1, 2, 3));
+    line(FIRST(5, 6, 7));
+    line(LAST());
+    line(LAST(9));
+    line(CALL(twice, 21));
+    line(SPREAD(4, 2));
+    line(AFTER);
+
+    int value = 10;
+    int ping = 3;
+    // self-referential macros are expanded only once
+#define value (value + 1)
+#define ping pong
+#define pong ping
+    line(value);
+    line(ping);
+#undef value
+    line(value);
+
+    // the predefined macros
+    line(__LINE__);
+    line(__LINE__ + 1);
+    line(strlen(__FILE__) > 0);
+    puts(__FILE__);
+    int first = __COUNTER__;
+    int second = __COUNTER__;
+    line(second - first);
+
+    // `#line` sets the number of the next line and the name of the file
+#line 500
+    line(__LINE__);
+#define RENAMED "renamed.c"
+#line 20 RENAMED
+    puts(__FILE__);
+    line(__LINE__);
+    return 0;
+}
@@ -0,0 +1,7 @@
+#pragma once
+
+#include "guarded.h"
+
+int doubled(int value) {
+    return value * 2;
+}
@@ -0,0 +1,88 @@
+#include <stdio.h>
+
+// a small state machine written with gotos
+int count_words(char* text) {
+    int words = 0;
+    int i = 0;
+space:
+    if (text[i] == 0) {
+        goto done;
+    }
+    if (text[i] == ' ') {
+        i++;
+        goto space;
+    }
+    words++;
+word:
+    i++;
+    if (text[i] == ' ') {
+        goto space;
+    }
+    if (text[i] != 0) {
+        goto word;
+    }
+done:
+    return words;
+}
+
+int main() {
+    // the body of a do-while runs at least once
+    int i = 10;
+    do {
+        putchar('0' + i % 10);
+        i++;
+    } while (i < 5);
+    putchar('\n');
+
+    i = 0;
+    do {
+        i++;
+        if (i == 2) {
+            continue;
+        }
+        if (i == 6) {
+            break;
+        }
+        putchar('0' + i);
+    } while (i < 8);
+    putchar('\n');
+
+    // continue in a while loop checks the condition again
+    i = 0;
+    while (i < 6) {
+        i++;
+        if (i % 2) {
+            continue;
+        }
+        putchar('0' + i);
+    }
+    putchar('\n');
+
+    // goto leaves nested loops
+    int found = 0;
+    for (int x = 0; x < 10; x++) {
+        for (int y = 0; y < 10; y++) {
+            if (x * y == 42) {
+                found = x * 10 + y;
+                goto out;
+            }
+        }
+    }
+out:
+    putchar('0' + found / 10);
+    putchar('0' + found % 10);
+    putchar('\n');
+
+    putchar('0' + count_words("  a state  machine "));
+    putchar('0' + count_words(""));
+    putchar('\n');
+
+    // a goto backwards loops
+    int n = 0;
+again:
+    n++;
+    if (n < 4) {
+        goto again;
+    }
+    return n;
+}
@@ -0,0 +1,121 @@
+#include <stdio.h>
+
+// enough cases close together to get a jump table
+int dense(int value) {
+    switch (value) {
+        case 1:
+            return 10;
+        case 2:
+            return 20;
+        case 3:
+        case 4:
+            return 34;
+        case 6:
+            return 60;
+        default:
+            return 0;
+    }
+    return -1;
+}
+
+// few cases far apart are compared one after another
+int sparse(long value) {
+    int result = 0;
+    switch (value) {
+        case -1000:
+            result = 1;
+            break;
+        case 7:
+            result = 2;
+            break;
+        case 5000000000:
+            result = 3;
+            break;
+    }
+    return result;
+}
+
+// every case falls through into the next one
+int fallthrough(int value) {
+    int result = 0;
+    switch (value) {
+        case 0:
+            result += 1;
+        case 1:
+            result += 2;
+        default:
+            result += 4;
+        case 2:
+            result += 8;
+    }
+    return result;
+}
+
+int letter(char c) {
+    switch (c) {
+        case 'a':
+            return 1;
+        case 'b':
+            return 2;
+        case -1:
+            return 3;
+    }
+    return 0;
+}
+
+int main() {
+    for (int i = 0; i < 8; i++) {
+        putchar('a' + dense(i) / 10);
+    }
+    putchar('\n');
+
+    putchar('0' + sparse(-1000L));
+    putchar('0' + sparse(7L));
+    putchar('0' + sparse(5000000000));
+    putchar('0' + sparse(8L));
+    putchar('\n');
+
+    for (int i = 0; i < 4; i++) {
+        putchar('a' + fallthrough(i));
+    }
+    putchar('\n');
+
+    char eof = -1;
+    putchar('0' + letter('a'));
+    putchar('0' + letter('b'));
+    putchar('0' + letter(eof));
+    putchar('0' + letter('z'));
+    putchar('\n');
+
+    // break leaves the switch, continue the loop around it
+    int count = 0;
+    for (int i = 0; i < 10; i++) {
+        switch (i % 3) {
+            case 0:
+                continue;
+            case 1:
+                switch (i) {
+                    case 4:
+                        count += 100;
+                        break;
+                    default:
+                        break;
+                }
+                count += 1;
+                break;
+        }
+        count += 10;
+    }
+    putchar('0' + count / 100);
+    putchar('0' + count / 10 % 10);
+    putchar('0' + count % 10);
+    putchar('\n');
+
+    // a switch without cases only runs its default
+    int x = 0;
+    switch (x) {
+        default:
+            x = 5;
+    }
+    return x;
+}
@@ -0,0 +1,103 @@
+#!/usr/bin/env python3
+"""Compiles every C program under tests/ and compares it against gcc.
+
+usage: python3 tests/test.py COMPILER [PROGRAM...]
+
+A program passes if the binary built from our assembly prints the same
+output and exits with the same status as the one gcc built.
+
+The programs under tests/errors/ have to be rejected instead. Every line
+with an error ends in a comment like `// error: UnknownCharacter 15`, and
+the compiler has to report exactly those errors in that program on exactly
+those lines and columns.
+"""
+
+import os
+import re
+import subprocess
+import sys
+import tempfile
+
+TESTS = os.path.dirname(os.path.abspath(__file__))
+
+
+def run(command, **kwargs):
+    return subprocess.run(command, capture_output=True, text=True, timeout=10, **kwargs)
+
+
+def check_rejected(compiler, program, directory):
+    with open(program) as file:
+        expected = [
+            (match.group(1), program, number, int(match.group(2)))
+            for number, text in enumerate(file, 1)
+            for match in re.finditer(r"// error: (\w+) (\d+)", text)
+        ]
+    result = run([compiler, program, os.path.join(directory, "test.s")])
+    if result.returncode == 0:
+        return "is not rejected"
+    # the first location after the name of an error is where it was found
+    errors = re.findall(
+        r'Error [\w ]+: (\w+) \{.*?file: "(.*?)",\s*line: (\d+),\s*column: (\d+),',
+        result.stdout,
+        re.S,
+    )
+    found = [(name, file, int(line), int(column)) for name, file, line, column in errors]
+    if expected != found:
+        return f"expected the errors {expected}\nfound {found}\n" + result.stdout
+    return None
+
+
+def check(compiler, program, directory):
+    if os.path.relpath(program, TESTS).startswith("errors" + os.sep):
+        return check_rejected(compiler, program, directory)
+
+    assembly = os.path.join(directory, "test.s")
+    ours = os.path.join(directory, "ours")
+    reference = os.path.join(directory, "reference")
+
+    result = run([compiler, program, assembly])
+    if result.returncode != 0:
+        return "does not compile:\n" + result.stdout + result.stderr
+    result = run(["gcc", "-o", ours, assembly])
+    if result.returncode != 0:
+        return "does not assemble:\n" + result.stderr
+    result = run(["gcc", "-w", "-I" + os.path.join(TESTS, "..", "include"), "-o", reference, program])
+    if result.returncode != 0:
+        return "gcc rejects the program:\n" + result.stderr
+
+    expected = run([reference])
+    found = run([ours])
+    if (expected.stdout, expected.returncode) != (found.stdout, found.returncode):
+        return (f"expected exit {expected.returncode} and output {expected.stdout!r}\n"
+                f"found exit {found.returncode} and output {found.stdout!r}")
+    return None
+
+
+def main():
+    if len(sys.argv) < 2:
+        sys.exit(__doc__)
+    compiler = os.path.abspath(sys.argv[1])
+    programs = sys.argv[2:] or sorted(
+        os.path.join(root, name)
+        for root, _, names in os.walk(TESTS)
+        for name in names
+        if name.endswith(".c")
+    )
+
+    failed = 0
+    with tempfile.TemporaryDirectory() as directory:
+        for program in programs:
+            error = check(compiler, program, directory)
+            name = os.path.relpath(program, TESTS)
+            if error is None:
+                print(f"ok      {name}")
+            else:
+                failed += 1
+                print(f"FAILED  {name}\n{error}")
+
+    print(f"\n{len(programs) - failed} passed, {failed} failed")
+    sys.exit(1 if failed else 0)
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,76 @@
+#include <stdio.h>
+
+int primes[] = {2, 3, 5, 7, 11};
+char greeting[] = "hey";
+static int counts[4];
+
+typedef struct Name {
+    char text[8];
+    int length;
+} Name;
+
+// an array parameter is a pointer to the first element
+int sum(int values[], int count) {
+    int total = 0;
+    for (int i = 0; i < count; i++) {
+        total += values[i];
+    }
+    return total;
+}
+
+int trace(int m[][4], int size) {
+    int total = 0;
+    for (int i = 0; i < size; i++) {
+        total += m[i][i];
+    }
+    return total;
+}
+
+int main() {
+    int a[5] = {1, 2};
+    // the elements without a value are zero
+    putchar('0' + a[0] + a[1] + a[2] + a[3] + a[4]);
+    putchar('0' + sizeof(a) / sizeof(a[0]));
+    putchar('0' + sum(a, 5));
+    putchar('\n');
+
+    int m[3][4];
+    for (int i = 0; i < 3; i++) {
+        for (int j = 0; j < 4; j++) {
+            m[i][j] = i * 4 + j;
+        }
+    }
+    putchar('A' + m[2][3]);
+    putchar('A' + m[1][0]);
+    putchar('0' + sizeof(m) / 8);
+    putchar('0' + sizeof(m[1]));
+    putchar('0' + trace(m, 3) % 10);
+    putchar('\n');
+
+    int grid[2][3] = {{1, 2, 3}, {4, 5}};
+    putchar('0' + grid[0][2] + grid[1][1] + grid[1][2]);
+    int* row = grid[1];
+    putchar('0' + row[0]);
+    putchar('0' + ((int*)&grid == &grid[0][0]));
+    putchar('\n');
+
+    char word[] = "array";
+    char buffer[8] = "ok";
+    putchar('0' + sizeof(word));
+    puts(word);
+    buffer[2] = '!';
+    puts(buffer);
+
+    Name name = {.text = "bob", .length = 3};
+    name.text[0] = 'B';
+    puts(name.text);
+    putchar('0' + sizeof(Name));
+    putchar('\n');
+
+    counts[primes[1]] = primes[4];
+    putchar('0' + sum(primes, 5) % 10);
+    putchar('0' + counts[3] - counts[0] - 10);
+    putchar('0' + sizeof(primes) / sizeof(int));
+    puts(greeting);
+    return sizeof(greeting);
+}
@@ -0,0 +1,77 @@
+#include <stdio.h>
+
+enum Color { RED, GREEN = 5, BLUE, ALPHA = -2, LAST, };
+
+typedef enum { IDLE, RUNNING = 'r', STOPPED } State;
+
+typedef enum Shape { CIRCLE = ~3, SQUARE } Shape;
+
+State step(State state) {
+    switch (state) {
+        case IDLE:
+            return RUNNING;
+        case RUNNING:
+            return STOPPED;
+        default:
+            return IDLE;
+    }
+}
+
+char name(enum Color color) {
+    switch (color) {
+        case RED:
+            return 'r';
+        case GREEN:
+            return 'g';
+        case BLUE:
+            return 'b';
+    }
+    return '?';
+}
+
+int main() {
+    putchar('0' + RED);
+    putchar('0' + GREEN);
+    putchar('0' + BLUE);
+    putchar('0' + ALPHA + 5);
+    putchar('0' + LAST + 5);
+    putchar('\n');
+
+    State state = IDLE;
+    for (int i = 0; i < 4; i++) {
+        state = step(state);
+        putchar('0' + state % 10);
+    }
+    putchar('\n');
+
+    enum Color color = BLUE;
+    putchar(name(color));
+    putchar(name(RED));
+    putchar(name(GREEN + BLUE));
+    putchar('\n');
+
+    // an inner variable hides an enumerator and enumerators work in
+    // any expression
+    Shape shape = SQUARE;
+    {
+        int RED = 7;
+        putchar('0' + RED);
+    }
+    putchar('0' + (shape == CIRCLE + 1));
+    putchar('0' + sizeof(enum Color));
+    putchar('\n');
+
+    // an inner block may declare its own enum with the same tag
+    {
+        enum Color { CYAN = 4, MAGENTA };
+        enum Color inner = MAGENTA;
+        putchar('0' + inner);
+    }
+    enum { SMALLEST = -2147483647 - 1, BIGGEST = 2147483647 };
+    putchar('0' + (BIGGEST + SMALLEST == -1));
+    putchar('0' + (LAST == -1));
+    putchar('\n');
+
+    enum { LOCAL = 3 };
+    return LOCAL * BLUE;
+}
@@ -0,0 +1,105 @@
+#include <stdio.h>
+#include <stdlib.h>
+
+typedef struct Node {
+    int value;
+    struct Node* next;
+} Node;
+
+// declared first, defined after the struct that points to it
+struct Owner;
+
+typedef struct Pet {
+    char name;
+    struct Owner* owner;
+} Pet;
+
+struct Owner {
+    char name;
+    Pet* pet;
+};
+
+union Number {
+    int whole;
+    char low;
+};
+
+typedef struct Tree Tree;
+
+struct Tree {
+    int value;
+    Tree* left;
+    Tree* right;
+};
+
+Node* push(Node* head, int value) {
+    Node* node = malloc(sizeof(Node));
+    node->value = value;
+    node->next = head;
+    return node;
+}
+
+int depth(Tree* tree) {
+    if (tree == 0) {
+        return 0;
+    }
+    int left = depth(tree->left);
+    int right = depth(tree->right);
+    if (left > right) {
+        return left + 1;
+    }
+    return right + 1;
+}
+
+int main() {
+    Node* list = 0;
+    for (int i = 1; i < 6; i++) {
+        list = push(list, i);
+    }
+    while (list) {
+        putchar('0' + list->value);
+        Node* next = list->next;
+        free(list);
+        list = next;
+    }
+    putchar('\n');
+
+    struct Owner owner;
+    Pet pet;
+    owner.name = 'A';
+    owner.pet = &pet;
+    pet.name = 'B';
+    pet.owner = &owner;
+    putchar(owner.pet->owner->name);
+    putchar(pet.owner->pet->name);
+    putchar('\n');
+
+    Tree leaf = {.value = 3, .left = 0, .right = 0};
+    Tree middle = {.value = 2, .left = &leaf, .right = 0};
+    Tree root = {.value = 1, .left = 0, .right = &middle};
+    putchar('0' + depth(&root));
+    putchar('0' + root.right->left->value);
+    putchar('\n');
+
+    // an inner block may define its own struct or union with the same tag
+    {
+        struct Node {
+            char tag;
+            long first;
+            long second;
+        };
+        struct Node shadow;
+        shadow.tag = 'C';
+        putchar(shadow.tag);
+        putchar('0' + sizeof(struct Node) / 8);
+        union Number {
+            long wide;
+            char bytes[16];
+        };
+        putchar('0' + sizeof(union Number) / 8);
+    }
+    putchar('0' + sizeof(struct Node) / 8);
+    putchar('0' + sizeof(union Number));
+    putchar('\n');
+    return 0;
+}
@@ -0,0 +1,109 @@
+#include <stdio.h>
+
+void print(unsigned long n) {
+    if (n >= 10) {
+        print(n / 10);
+    }
+    putchar('0' + n % 10);
+}
+
+void line(unsigned long n) {
+    print(n);
+    putchar('\n');
+}
+
+int main() {
+    putchar('0' + sizeof(short));
+    putchar('0' + sizeof(unsigned short int));
+    putchar('0' + sizeof(long long));
+    putchar('0' + sizeof(unsigned));
+    putchar('0' + sizeof(_Bool));
+    putchar('0' + sizeof(signed char));
+    putchar('0' + sizeof(long unsigned int));
+    putchar('0' + sizeof(unsigned long long));
+    putchar('\n');
+
+    // division
+    unsigned int big = 4000000000;
+    line(big / 3);
+    line(big % 7);
+    int negative = -7;
+    putchar('0' - negative / 2);
+    putchar('0' - negative % 2);
+    long wide = 10000000000;
+    line(wide / 7);
+    unsigned long huge = 18000000000000000000UL;
+    line(huge / 1000000000);
+    unsigned char byte = 250;
+    putchar('0' + byte / 50);
+    char signed_byte = -6;
+    putchar('0' - signed_byte / 3);
+    short s = -300;
+    putchar('0' - s / 100);
+    putchar('\n');
+
+    // comparisons
+    unsigned int zero = 0;
+    int signed_zero = 0;
+    putchar('0' + (zero - 1 > 5));
+    putchar('0' + (signed_zero - 1 > 5));
+    putchar('0' + (big > 5));
+    putchar('0' + (big <= 5));
+    unsigned char high = 200;
+    putchar('0' + (high >= 100));
+    putchar('\n');
+
+    // shifts
+    unsigned int top = 2147483648;
+    line(top >> 31);
+    int minus = -16;
+    putchar('0' - (minus >> 2));
+    putchar('0' + (high >> 6));
+    unsigned long ones = 18446744073709551615UL;
+    line(ones >> 60);
+
+    // _Bool only holds 0 or 1
+    _Bool flag = 5;
+    putchar('0' + flag);
+    flag = 0;
+    flag++;
+    putchar('0' + flag);
+    flag += 4;
+    putchar('0' + flag);
+    flag = (_Bool)256;
+    putchar('0' + flag);
+    _Bool pointer = &negative;
+    putchar('0' + pointer);
+    putchar('\n');
+
+    // unsigned values wrap around
+    unsigned char wrap = 255;
+    wrap++;
+    putchar('0' + wrap);
+    unsigned short counter = 65535;
+    counter += 2;
+    putchar('0' + counter);
+    putchar('\n');
+
+    // literals get the first type their value fits in
+    putchar('0' + (-1 < 1U));
+    putchar('0' + sizeof(0xFFFFFFFF));
+    putchar('0' + (0xFFFFFFFF > 0));
+    putchar('0' + ((1U << 31) > 0));
+    putchar('0' + (-1 / 2U > 5));
+    putchar('0' + sizeof(4294967295));
+    putchar('0' + sizeof(1U));
+    putchar('0' + sizeof(0x7FFFFFFF));
+    putchar('0' + sizeof(0x100000000));
+    putchar('0' + (0xFFFFFFFFFFFFFFFF > 0));
+    putchar('0' + sizeof(020000000000));
+    putchar('0' + (020000000000 > 0));
+    putchar('0' + (-1L < 1U));
+    putchar('\n');
+
+    _Static_assert(-1 > 1U, "unsigned literals");
+    _Static_assert(0xFFFFFFFF + 1 == 0, "hex literals may be unsigned");
+    _Static_assert((unsigned int)-1 / 2 == 2147483647, "unsigned constants");
+    _Static_assert(-1 < 0, "signed constants");
+    return 0;
+}
@@ -0,0 +1,93 @@
+#include <stdio.h>
+#include <stddef.h>
+
+typedef struct Small {
+    char c;
+    int x;
+} Small;
+
+typedef struct Mixed {
+    char a;
+    long b;
+    char c;
+} Mixed;
+
+typedef struct Values {
+    char tag;
+    int values[3];
+    char end;
+} Values;
+
+typedef struct Outer {
+    char c;
+    Mixed inner;
+    int last;
+} Outer;
+
+typedef union Bytes {
+    char c[5];
+    int i;
+} Bytes;
+
+typedef struct Tagged {
+    char tag;
+    Bytes bytes;
+} Tagged;
+
+_Static_assert(offsetof(Outer, inner.b) == 16, "offsets are constants");
+
+void print(int n) {
+    if (n >= 10) {
+        print(n / 10);
+    }
+    putchar('0' + n % 10);
+}
+
+void layout(int size, int align) {
+    print(size);
+    putchar(' ');
+    print(align);
+    putchar('\n');
+}
+
+int kind(int offset) {
+    switch (offset) {
+        case offsetof(Small, x):
+            return 1;
+        case offsetof(Mixed, c):
+            return 2;
+    }
+    return 0;
+}
+
+int main() {
+    layout(sizeof(Small), _Alignof(Small));
+    layout(sizeof(Mixed), _Alignof(Mixed));
+    layout(sizeof(Values), _Alignof(Values));
+    layout(sizeof(Outer), _Alignof(Outer));
+    layout(sizeof(Bytes), _Alignof(Bytes));
+    layout(sizeof(Tagged), _Alignof(Tagged));
+    layout(_Alignof(char), _Alignof(long));
+
+    print(offsetof(Small, x));
+    print(offsetof(Mixed, b));
+    print(offsetof(Mixed, c));
+    print(offsetof(Values, end));
+    print(offsetof(Outer, last));
+    print(offsetof(Tagged, bytes));
+    putchar('\n');
+
+    Mixed pair[3];
+    layout(sizeof(pair), _Alignof(pair));
+
+    // the fields are where gcc puts them
+    Outer outer;
+    outer.inner.b = 77;
+    outer.last = 5;
+    char* base = (char*)&outer;
+    print(*(long*)(base + offsetof(Outer, inner) + offsetof(Mixed, b)));
+    print(*(int*)(base + offsetof(Outer, last)));
+    print(kind(4) * 10 + kind(16));
+    putchar('\n');
+    return 0;
+}
@@ -0,0 +1,75 @@
+#include <stdio.h>
+
+typedef union Number {
+    char c;
+    int i;
+    long l;
+} Number;
+
+typedef struct Pair {
+    int first;
+    int second;
+} Pair;
+
+// a tagged union, the tag tells which field is in use
+typedef struct Value {
+    int tag;
+    int padding;
+    union {
+        int number;
+        Pair pair;
+        char* text;
+    } as;
+} Value;
+
+int total(Value* value) {
+    switch (value->tag) {
+        case 0:
+            return value->as.number;
+        case 1:
+            return value->as.pair.first + value->as.pair.second;
+    }
+    int digit = value->as.text[0] - '0';
+    return digit;
+}
+
+int main() {
+    Number n;
+    n.l = 0;
+    n.i = 0x4142;
+    // all fields start at the same address
+    putchar(n.c);
+    putchar('0' + (&n.c == (char*)&n.l));
+    putchar('0' + sizeof(Number));
+    putchar('0' + sizeof(union { char c; int i; }));
+    putchar('0' + sizeof(Value) % 10);
+    putchar('\n');
+
+    Value number = {.tag = 0, .as = {.number = 5}};
+    Value pair = {.as = {.pair = {.second = 2, .first = 4}}, .tag = 1};
+    Value text = {.tag = 2, .as = {.text = "7"}};
+    Value values[] = {number, pair, text};
+    for (int i = 0; i < 3; i++) {
+        putchar('0' + total(&values[i]));
+    }
+    putchar('\n');
+
+    // assignments through pointers and nested fields
+    Value* p = &values[1];
+    p->as.pair.second = 3;
+    p->as.pair.first += 1;
+    putchar('0' + total(p));
+    p->tag = 0;
+    p->as.number = 8;
+    putchar('0' + total(p));
+    putchar('\n');
+
+    Number copy = n;
+    copy.c = 'z';
+    putchar(copy.c);
+    putchar(n.c);
+    putchar('\n');
+
+    Number init = {.i = 9};
+    return init.i;
+}